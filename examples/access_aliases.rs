@@ -0,0 +1,41 @@
+use register_block::register_block;
+
+// access strings as copied from a vendor datasheet
+#[register_block]
+pub struct Gpio {
+    #[register(offset = 0x00, access = "read-write")]
+    mode: u32,
+    #[register(offset = 0x04, access = "ReadOnly")]
+    input: u32,
+    #[register(offset = 0x08, access = "w/o")]
+    set: u32,
+    #[register(offset = 0x0C, access = "Write_Clear")]
+    irq_clear: u32,
+    #[register(offset = 0x10, access = "rw")]
+    pull: u32,
+    // This should cause a compile error pointing at "read-mostly": unknown access type
+    // #[register(offset = 0x14, access = "read-mostly")]
+    // typo: u32,
+}
+
+// This should cause a compile error: "ReadOnly" registers have no `write`
+// fn poke(gpio: &Gpio<usize>) {
+//     gpio.input().write(1);
+// }
+
+fn main() {
+    let mut buffer = [0u32; 5];
+    buffer[1] = 0x0F;
+    buffer[3] = 0xFF;
+    let gpio = Gpio::new(buffer.as_mut_ptr() as usize);
+
+    gpio.mode().write(0x5);
+    assert_eq!(gpio.mode().read(), 0x5);
+    assert_eq!(gpio.input().read(), 0x0F);
+    gpio.set().write(0x3);
+    assert_eq!(buffer[2], 0x3);
+    gpio.irq_clear().clear();
+    assert_eq!(buffer[3], 0);
+    gpio.pull().modify(|pull| pull | 0x10);
+    assert_eq!(buffer[4], 0x10);
+}
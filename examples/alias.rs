@@ -0,0 +1,44 @@
+use register_block::register_block;
+
+// register types as named in the vendor headers
+type Reg32 = u32;
+type Reg16 = u16;
+// This should cause a compile error: the alias doesn't name an integer
+// type Reg32 = [u8; 4];
+
+#[register_block]
+pub struct Spi {
+    #[register(offset = 0x00, access = "RW", width = 32)]
+    #[bits(name = "enable", range = 0..1)]
+    #[bits(name = "prescaler", range = 8..16)]
+    ctrl: Reg32,
+    #[register(offset = 0x04, access = "RO", width = 16)]
+    status: Reg16,
+    #[register(offset = 0x08, access = "RW", width = 32, count = 2)]
+    data: Reg32,
+    // This should cause a compile error: `Reg32` is 4 bytes, not 2
+    // #[register(offset = 0x10, access = "RW", width = 16)]
+    // wrong_width: Reg32,
+    // This should cause a compile error: an alias needs its width spelled out
+    // #[register(offset = 0x10, access = "RW")]
+    // no_width: Reg32,
+    // This should cause a compile error: the range exceeds the declared width
+    // #[register(offset = 0x10, access = "RW", width = 16)]
+    // #[bits(name = "too_wide", range = 12..20)]
+    // narrow: Reg16,
+}
+
+fn main() {
+    assert_eq!(Spi::<usize>::DATA_OFFSET, 0x08);
+    assert_eq!(Spi::<usize>::BLOCK_SIZE, 0x10);
+
+    let mut buffer = [0u32; 4];
+    buffer[1] = 0x0000_0042;
+    let spi = Spi::new(buffer.as_mut_ptr() as usize);
+    spi.set_enable(true);
+    spi.set_prescaler(0x12);
+    assert_eq!(spi.ctrl().read(), 0x0000_1201);
+    assert_eq!(spi.status().read(), 0x42);
+    spi.data(1).write(7);
+    assert_eq!(buffer[3], 7);
+}
@@ -0,0 +1,22 @@
+use register_block::{Aligned, RO, RW};
+
+fn main() {
+    // 32-bit registers on a bus decoding only 64-bit aligned addresses
+    let mut buffer = [0u64; 2];
+    let base = buffer.as_mut_ptr() as usize;
+    let ctrl = unsafe { Aligned::<RW<u32>, 8>::new(base) };
+    let status = unsafe { Aligned::<RO<u32>, 8>::new(base + 8) };
+    ctrl.write(0x1234);
+    ctrl.modify(|v| v | 1);
+    assert_eq!(ctrl.read(), 0x1235);
+    assert_eq!(status.read(), 0);
+
+    // 4-byte aligned is enough for a plain `RW<u32>`, but not for this one
+    let unaligned = base + 4;
+    let _plain = unsafe { RW::<u32>::new(unaligned) };
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| unsafe { Aligned::<RW<u32>, 8>::new(unaligned) }).is_err());
+
+    // This should cause a compile error: ALIGN is smaller than the alignment of `u32`
+    // let _ = unsafe { Aligned::<RW<u32>, 2>::new(base) };
+}
@@ -0,0 +1,83 @@
+use register_block::register_block;
+
+#[register_block(flat)]
+pub struct Dma {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x40, access = "RW", count = 8, stride = 4)]
+    chan: u32,
+    // stride defaults to the size of the register
+    #[register(offset = 0x60, access = "RO", count = 4)]
+    #[bits(name = "chan_busy", range = 0..1)]
+    status: u32,
+    // This should cause a compile error: 0x50 lies inside the `chan` array
+    // #[register(offset = 0x50, access = "RW")]
+    // inside_chan: u32,
+}
+
+#[register_block]
+pub struct Gpio {
+    #[register(offset = 0x10, access = "RW", count = 4, stride = 8)]
+    bank: u16,
+}
+
+fn main() {
+    let mut buffer = [0u32; 0x20];
+    let base = buffer.as_mut_ptr() as usize;
+    let dma = Dma::new(base);
+
+    assert_eq!(dma.chan_len(), 8);
+    assert_eq!(dma.status_len(), 4);
+    for i in 0..dma.chan_len() {
+        dma.write_chan(i, i as u32 * 10);
+    }
+    dma.modify_chan(3, |v| v + 1);
+    for i in 0..8 {
+        let expected = i as u32 * 10 + u32::from(i == 3);
+        assert_eq!(
+            unsafe { (base as *const u32).add(0x10 + i).read() },
+            expected
+        );
+        assert_eq!(dma.read_chan(i), expected);
+    }
+
+    unsafe { (base as *mut u32).add(0x18 + 2).write(1) };
+    assert!(!dma.chan_busy(1));
+    assert!(dma.chan_busy(2));
+
+    let gpio = Gpio::new(base);
+    assert_eq!(gpio.bank_len(), 4);
+    gpio.bank(2).write(0xBEEF);
+    assert_eq!(gpio.ptr_bank(3) as usize, base + 0x10 + 3 * 8);
+    assert_eq!(
+        unsafe { ((base + 0x10 + 2 * 8) as *const u16).read() },
+        0xBEEF
+    );
+
+    // batch reads start at index 0 and follow the stride
+    let mut drained = [0u32; 5];
+    dma.read_chan_into(&mut drained);
+    assert_eq!(drained, [0, 10, 20, 31, 40]);
+    gpio.bank(0).write(0x1111);
+    gpio.bank(1).write(0x2222);
+    let mut banks = [0u16; 3];
+    gpio.read_bank_into(&mut banks);
+    assert_eq!(banks, [0x1111, 0x2222, 0xBEEF]);
+
+    // the array proxy indexes like `bank[3]`
+    let bank = gpio.bank_array();
+    assert_eq!(bank.len(), 4);
+    bank.at(3).write(0x4444);
+    assert_eq!(bank.at(2).read(), 0xBEEF);
+    assert_eq!(bank.at(3).read(), 0x4444);
+    assert!(bank.get(4).is_none());
+    let all: Vec<u16> = bank.iter().map(|reg| reg.read()).collect();
+    assert_eq!(all, [0x1111, 0x2222, 0xBEEF, 0x4444]);
+
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| dma.read_chan(8)).is_err());
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| dma.read_chan_into(&mut [0; 9])).is_err());
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| gpio.bank_array().at(4).read()).is_err());
+}
@@ -0,0 +1,40 @@
+use core::sync::atomic::Ordering;
+use register_block::AtomicRW;
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    let base = buffer.as_mut_ptr() as usize;
+    let reg = unsafe { AtomicRW::<u32>::new(base) };
+
+    reg.store(0b1100, Ordering::Relaxed);
+    assert_eq!(reg.load(Ordering::Relaxed), 0b1100);
+    assert_eq!(reg.fetch_or(0b0011, Ordering::AcqRel), 0b1100);
+    assert_eq!(reg.fetch_and(0b1010, Ordering::AcqRel), 0b1111);
+    assert_eq!(reg.fetch_xor(0b1111, Ordering::AcqRel), 0b1010);
+    assert_eq!(reg.load(Ordering::Acquire), 0b0101);
+    assert_eq!(buffer[0], 0b0101);
+
+    let half = unsafe { AtomicRW::<u16>::new(buffer.as_mut_ptr() as usize + 4) };
+    assert_eq!(half.fetch_or(0x8001, Ordering::SeqCst), 0);
+    assert_eq!(half.fetch_and(0x8000, Ordering::SeqCst), 0x8001);
+    assert_eq!(half.load(Ordering::SeqCst), 0x8000);
+
+    // an interrupt handler sets a flag between the read and the write of the first attempt,
+    // so the update is retried on top of it instead of losing the flag
+    let irq = unsafe { AtomicRW::<u32>::new(base) };
+    let mut attempts = 0;
+    let result = reg.fetch_update(Ordering::AcqRel, Ordering::Acquire, |status| {
+        attempts += 1;
+        if attempts == 1 {
+            irq.fetch_or(0b1000_0000, Ordering::Relaxed);
+        }
+        Some(status & !0b0001)
+    });
+    assert_eq!(result, Ok(0b1000_0101));
+    assert!(attempts >= 2);
+    assert_eq!(reg.load(Ordering::Relaxed), 0b1000_0100);
+
+    // the closure can give up without writing anything
+    let result = reg.fetch_update(Ordering::AcqRel, Ordering::Acquire, |_| None);
+    assert_eq!(result, Err(0b1000_0100));
+}
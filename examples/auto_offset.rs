@@ -0,0 +1,51 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Dac {
+    // registers without an offset follow the previous one, like a packed C struct
+    #[register(access = "RW")]
+    ctrl: u32,
+    #[register(access = "RO")]
+    status: u32,
+    #[register(access = "RW")]
+    data_lo: u16,
+    #[register(access = "RW")]
+    data_hi: u16,
+    #[register(access = "WO", count = 4)]
+    fifo: u32,
+    // an explicit offset moves the cursor
+    #[register(offset = 0x40, access = "RW")]
+    trim: u8,
+    #[register(access = "RO")]
+    revision: u8,
+    #[register(access = "RO")]
+    id: u16,
+    // This should cause a compile error: explicit offsets are overlap-checked against the
+    // computed ones, and 0x43 lies inside `id`
+    // #[register(offset = 0x43, access = "RW")]
+    // late: u8,
+    // This should cause a compile error: a u32 at 0x46 wouldn't be aligned
+    // #[register(offset = 0x44, access = "RW")]
+    // pad: u16,
+    // #[register(access = "RW")]
+    // misaligned: u32,
+}
+
+fn main() {
+    assert_eq!(Dac::<usize>::CTRL_OFFSET, 0x00);
+    assert_eq!(Dac::<usize>::STATUS_OFFSET, 0x04);
+    assert_eq!(Dac::<usize>::DATA_LO_OFFSET, 0x08);
+    assert_eq!(Dac::<usize>::DATA_HI_OFFSET, 0x0A);
+    assert_eq!(Dac::<usize>::FIFO_OFFSET, 0x0C);
+    assert_eq!(Dac::<usize>::TRIM_OFFSET, 0x40);
+    assert_eq!(Dac::<usize>::REVISION_OFFSET, 0x41);
+    assert_eq!(Dac::<usize>::ID_OFFSET, 0x42);
+    assert_eq!(Dac::<usize>::BLOCK_SIZE, 0x44);
+
+    let mut buffer = [0u32; 0x44 / 4];
+    let dac = Dac::new(buffer.as_mut_ptr() as usize);
+    dac.fifo(3).write(0xABCD);
+    dac.data_hi().write(0x1234);
+    assert_eq!(buffer[(0x0C + 3 * 4) / 4], 0xABCD);
+    assert_eq!(buffer[0x08 / 4] >> 16, 0x1234);
+}
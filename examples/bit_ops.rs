@@ -0,0 +1,43 @@
+use register_block::RW;
+
+fn main() {
+    let mut buffer = [0u32; 1];
+    let reg = unsafe { RW::<u32>::new(buffer.as_mut_ptr() as usize) };
+
+    reg.set_bit(3);
+    reg.set_bit(31);
+    assert_eq!(reg.read(), 0x8000_0008);
+    assert!(reg.is_bit_set(3));
+    assert!(!reg.is_bit_set(4));
+
+    reg.clear_bit(31);
+    assert_eq!(reg.read(), 0x8);
+    reg.toggle_bit(0);
+    reg.toggle_bit(3);
+    assert_eq!(reg.read(), 0x1);
+
+    // only the bits in the mask change
+    reg.write(0xFFFF_0000);
+    reg.write_masked(0x1234_5678, 0x00FF_FF00);
+    assert_eq!(reg.read(), 0xFF34_5600);
+
+    // a 4-bit field at bits 8..12
+    reg.write(0xFFFF_A5FF);
+    assert_eq!(reg.read_bits(8..12), 0x5);
+    reg.write_bits(8..12, 0xC);
+    assert_eq!(reg.read(), 0xFFFF_ACFF);
+    // bits above the field width are dropped
+    reg.write_bits(8..12, 0xF3);
+    assert_eq!(reg.read(), 0xFFFF_A3FF);
+    assert_eq!(reg.read_bits(0..32), 0xFFFF_A3FF);
+
+    let mut byte = [0u8; 1];
+    let small = unsafe { RW::<u8>::new(byte.as_mut_ptr() as usize) };
+    small.set_bit(7);
+    assert_eq!(small.read(), 0x80);
+
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| small.set_bit(8)).is_err());
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| small.read_bits(6..9)).is_err());
+}
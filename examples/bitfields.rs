@@ -0,0 +1,107 @@
+use register_block::{register_block, FieldOverflow};
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    None = 0,
+    Even = 1,
+    Odd = 2,
+}
+
+impl TryFrom<u8> for Parity {
+    /// the undefined raw value
+    type Error = u8;
+    fn try_from(raw: u8) -> Result<Self, u8> {
+        match raw {
+            0 => Ok(Parity::None),
+            1 => Ok(Parity::Even),
+            2 => Ok(Parity::Odd),
+            other => Err(other),
+        }
+    }
+}
+
+impl From<Parity> for u8 {
+    fn from(parity: Parity) -> u8 {
+        parity as u8
+    }
+}
+
+#[register_block]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RW")]
+    #[bits(name = "enable", range = 0..1)]
+    #[bits(name = "parity", range = 1..3)]
+    #[bits(name = "baud_div", range = 8..=23)]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    #[bits(name = "busy", range = 0..1)]
+    #[bits(name = "fifo_level", range = 4..8)]
+    status: u32,
+    #[register(offset = 0x08, access = "RW")]
+    #[field(name = "line_parity", range = 4..6, ty = Parity)]
+    #[bits(name = "loopback", range = 0..1)]
+    line: u32,
+    // This should cause a compile error: the range exceeds the register width
+    // #[register(offset = 0x08, access = "RW")]
+    // #[bits(name = "too_wide", range = 4..9)]
+    // small: u8,
+    // This should cause a compile error: the ranges overlap
+    // #[register(offset = 0x0C, access = "RW")]
+    // #[bits(name = "low", range = 0..4)]
+    // #[bits(name = "high", range = 3..8)]
+    // overlapping: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 3];
+    buffer[0] = 0xFF00_0000;
+    buffer[1] = 0b1010_0001;
+    let uart = Uart::new(buffer.as_mut_ptr() as usize);
+
+    uart.set_enable(true);
+    assert!(uart.enable());
+    uart.set_parity(0b10);
+    assert_eq!(uart.parity(), 0b10);
+    uart.set_baud_div(0x1234);
+    assert_eq!(uart.baud_div(), 0x1234);
+    // bits outside the declared fields are preserved
+    assert_eq!(uart.ctrl().read(), 0xFF12_3405);
+
+    // setters clear the old bits before inserting the new value
+    uart.set_parity(0b01);
+    uart.set_enable(false);
+    assert_eq!(uart.ctrl().read(), 0xFF12_3402);
+
+    assert!(uart.busy());
+    assert_eq!(uart.fifo_level(), 0b1010);
+
+    uart.set_loopback(true);
+    uart.set_line_parity(Parity::Odd);
+    assert_eq!(uart.line_parity(), Ok(Parity::Odd));
+    assert_eq!(uart.line().read(), 0b10_0001);
+    uart.set_line_parity(Parity::Even);
+    assert_eq!(uart.line_parity(), Ok(Parity::Even));
+    // undefined bit patterns surface the `TryFrom` error
+    uart.line().write(0b11_0000);
+    assert_eq!(uart.line_parity(), Err(0b11));
+
+    // values too large for a field are rejected or clamped instead of truncated
+    uart.ctrl().write(0);
+    assert_eq!(
+        uart.try_set_parity(0b100),
+        Err(FieldOverflow {
+            value: 0b100,
+            max: 0b11
+        })
+    );
+    assert_eq!(uart.ctrl().read(), 0);
+    assert_eq!(uart.try_set_parity(0b11), Ok(()));
+    assert_eq!(uart.parity(), 0b11);
+    uart.set_baud_div_saturating(0x1234);
+    assert_eq!(uart.baud_div(), 0x1234);
+    uart.set_parity_saturating(7);
+    assert_eq!(uart.parity(), 0b11);
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| uart.set_parity(0b100)).is_err());
+}
@@ -0,0 +1,49 @@
+use core::mem::{offset_of, size_of};
+use register_block::register_block;
+
+#[register_block(c_layout)]
+pub struct Timer {
+    /// Control register.
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u16,
+    #[register(offset = 0x10, access = "RW", count = 4)]
+    compare: u32,
+    #[register(offset = 0x28, access = "WO")]
+    load: u64,
+    // This should cause a compile error: `#[repr(C)]` would pad it to 0x08
+    // #[register(offset = 0x06, access = "RW", unchecked)]
+    // misaligned: u32,
+    // This should cause a compile error: the elements are 8 bytes apart
+    // #[register(offset = 0x40, access = "RW", count = 2, stride = 8)]
+    // spread: u32,
+}
+
+// a C driver's view of the same registers
+extern "C" fn kick(timer: *mut TimerLayout) {
+    unsafe { core::ptr::addr_of_mut!((*timer).ctrl).write_volatile(1) };
+}
+
+fn main() {
+    assert_eq!(offset_of!(TimerLayout, ctrl), Timer::<usize>::CTRL_OFFSET);
+    assert_eq!(
+        offset_of!(TimerLayout, status),
+        Timer::<usize>::STATUS_OFFSET
+    );
+    assert_eq!(
+        offset_of!(TimerLayout, compare),
+        Timer::<usize>::COMPARE_OFFSET
+    );
+    assert_eq!(offset_of!(TimerLayout, load), Timer::<usize>::LOAD_OFFSET);
+    assert_eq!(size_of::<TimerLayout>(), Timer::<usize>::BLOCK_SIZE);
+
+    let mut buffer = [0u64; 6];
+    let base = buffer.as_mut_ptr() as usize;
+    let timer = Timer::new(base);
+    timer.compare(2).write(0xCAFE);
+    kick(base as *mut TimerLayout);
+    let layout = unsafe { &*(base as *const TimerLayout) };
+    assert_eq!(layout.compare[2], 0xCAFE);
+    assert_eq!(timer.ctrl().read(), 1);
+}
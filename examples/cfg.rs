@@ -0,0 +1,55 @@
+use register_block::register_block;
+
+#[register_block(derive_debug)]
+#[init(ctrl = 1, wide_ctrl = 2)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    // only on 16-bit parts, so never on the machines this example runs on
+    #[cfg(target_pointer_width = "16")]
+    #[register(offset = 0x04, access = "RW", reset = 0)]
+    #[bits(name = "prescale", range = 0..4)]
+    narrow_ctrl: u16,
+    #[cfg(not(target_pointer_width = "16"))]
+    #[register(offset = 0x08, access = "RW", reset = 0)]
+    wide_ctrl: u32,
+    // two variants of the same register, which don't count as overlapping as only one of
+    // them ever exists
+    #[cfg(target_pointer_width = "16")]
+    #[register(offset = 0x0C, access = "RW")]
+    narrow_status: u16,
+    #[cfg(not(target_pointer_width = "16"))]
+    #[register(offset = 0x0C, access = "RO")]
+    wide_status: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 4];
+    let timer = Timer::new(buffer.as_mut_ptr() as usize);
+
+    timer.apply_init();
+    assert_eq!(buffer, [1, 0, 2, 0]);
+    timer.reset_all();
+    assert_eq!(buffer, [1, 0, 0, 0]);
+    assert_eq!(Timer::<usize>::WIDE_CTRL_OFFSET, 0x08);
+    assert_eq!(Timer::<usize>::WIDE_STATUS_OFFSET, 0x0C);
+    assert_eq!(Timer::<usize>::BLOCK_SIZE, 0x10);
+    assert_eq!(
+        format!("{:?}", timer),
+        "Timer { ctrl: 0x1, wide_ctrl: 0x0, wide_status: 0x0 }"
+    );
+    // the memory map only lists the registers that exist
+    assert_eq!(
+        Timer::<usize>::MEMORY_MAP
+            .lines()
+            .skip(2)
+            .map(|row| row.split('`').nth(1).unwrap())
+            .collect::<Vec<_>>(),
+        ["ctrl", "wide_ctrl", "wide_status"]
+    );
+    // This should cause a compile error: `narrow_ctrl` and its helpers don't exist here
+    // timer.narrow_ctrl().write(1);
+    // timer.set_prescale(3);
+    // let _ = Timer::<usize>::NARROW_CTRL_OFFSET;
+    // let _ = Timer::<usize>::NARROW_STATUS_OFFSET;
+}
@@ -0,0 +1,68 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Irq {
+    #[register(offset = 0x00, access = "WC")]
+    zero_clear: u32,
+    // writing all ones clears this register
+    #[register(offset = 0x04, access = "WC", clear_value = 0xFFFF_FFFF)]
+    ones_clear: u32,
+    #[register(offset = 0x08, access = "W1C")]
+    status: u32,
+    // This should be allowed: W1C counts as write-side, so a RO view may share its offset
+    #[register(offset = 0x08, access = "RO")]
+    status_ro: u32,
+    // This should cause a compile error: clear_value only makes sense on WC registers
+    // #[register(offset = 0x08, access = "RW", clear_value = 0)]
+    // not_clear: u32,
+}
+
+#[register_block(flat)]
+pub struct FlatIrq {
+    #[register(offset = 0x04, access = "WC", clear_value = 0xFFFF_FFFF)]
+    ones_clear: u32,
+    #[register(offset = 0x08, access = "W1C")]
+    status: u32,
+    // readable and write-one-to-clear, and claims both sides of its offset
+    #[register(offset = 0x0C, access = "RW1C")]
+    flags: u32,
+    // This should cause a compile error: unlike W1C, RW1C can't share its offset with a RO view
+    // #[register(offset = 0x0C, access = "RO")]
+    // flags_ro: u32,
+}
+
+fn main() {
+    let mut buffer = [0x1234u32; 4];
+    let base = buffer.as_mut_ptr() as usize;
+    let irq = Irq::new(base);
+    let word = |index: usize| unsafe { (base as *const u32).add(index).read() };
+
+    irq.zero_clear().clear();
+    assert_eq!(word(0), 0);
+    irq.ones_clear().clear();
+    assert_eq!(word(1), 0xFFFF_FFFF);
+    irq.zero_clear().clear_with(0x5);
+    assert_eq!(word(0), 0x5);
+
+    unsafe { (base as *mut u32).add(1).write(0) };
+    let flat = FlatIrq::new(base);
+    flat.clear_ones_clear();
+    assert_eq!(word(1), 0xFFFF_FFFF);
+
+    // the peripheral raises some status flags ...
+    unsafe { (base as *mut u32).add(2).write(0b1011) };
+    assert_eq!(irq.status().read(), 0b1011);
+    assert_eq!(flat.read_status(), irq.status_ro().read());
+    // ... and the driver acknowledges a subset by writing exactly those bits
+    irq.status().ack(0b0011);
+    assert_eq!(word(2), 0b0011);
+    flat.ack_status(0b1000);
+    assert_eq!(word(2), 0b1000);
+
+    // RW1C flags are read, then cleared by writing ones to the flags to clear
+    unsafe { (base as *mut u32).add(3).write(0b0110) };
+    let pending = flat.read_flags();
+    assert_eq!(pending, 0b0110);
+    flat.clear_bits_flags(pending & 0b0100);
+    assert_eq!(word(3), 0b0100);
+}
@@ -0,0 +1,35 @@
+use register_block::register_block;
+
+#[register_block]
+#[compose(name = "count", parts = [count_lo, count_hi], ty = u64)]
+#[compose(name = "stamp", parts = [stamp_lo, stamp_mid, stamp_hi], ty = u32)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RO")]
+    count_lo: u32,
+    #[register(offset = 0x04, access = "RO")]
+    count_hi: u32,
+    #[register(offset = 0x08, access = "RW")]
+    stamp_lo: u8,
+    #[register(offset = 0x0A, access = "RW")]
+    stamp_mid: u16,
+    #[register(offset = 0x0C, access = "RO")]
+    stamp_hi: u8,
+    #[register(offset = 0x10, access = "WO")]
+    reload: u32,
+    // This should cause a compile error: adding `reload` to the parts of a #[compose], WO can't be read
+    // This should cause a compile error: adding `missing` to the parts of a #[compose], it isn't a register
+    // This should cause a compile error: `ty = u32` for `count`, its parts take 64 bits
+}
+
+fn main() {
+    let mut buffer = [0u32; 5];
+    buffer[0] = 0x8765_4321;
+    buffer[1] = 0x0000_00AB;
+    buffer[2] = 0xBEEF_0012;
+    buffer[3] = 0x0000_0034;
+    let base = buffer.as_mut_ptr() as usize;
+    let timer = Timer::new(base);
+
+    assert_eq!(timer.read_count(), 0xAB_8765_4321);
+    assert_eq!(timer.read_stamp(), 0x34BE_EF12);
+}
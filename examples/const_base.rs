@@ -0,0 +1,46 @@
+use register_block::{register_block, ConstantAddress};
+
+// a single-instance peripheral at a fixed address
+#[register_block(base = 0x4000_2000)]
+pub struct Rcc {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x08, access = "RO")]
+    status: u32,
+    #[register(offset = 0x10, access = "RW", count = 4)]
+    enable: u32,
+}
+
+// the constructor takes no arguments and is `const`, so the block can live in a static
+static RCC: Rcc<ConstantAddress<0x4000_2000>> = Rcc::at_const();
+
+// on constant bases, register addresses can be computed at compile time
+const STATUS_ADDRESS: usize = Rcc::at_const().addr_status();
+const ENABLE_ADDRESSES: [usize; 4] = {
+    let mut addresses = [0; 4];
+    let mut index = 0;
+    while index < addresses.len() {
+        addresses[index] = Rcc::at_const().addr_enable(index);
+        index += 1;
+    }
+    addresses
+};
+
+fn main() {
+    assert_eq!(Rcc::BASE, 0x4000_2000);
+    assert_eq!(RCC.base_address(), 0x4000_2000);
+    assert_eq!(RCC.ptr_status() as usize, 0x4000_2008);
+    assert_eq!(Rcc::at_const().ptr_ctrl() as usize, 0x4000_2000);
+    assert_eq!(STATUS_ADDRESS, 0x4000_2008);
+    assert_eq!(
+        ENABLE_ADDRESSES,
+        [0x4000_2010, 0x4000_2014, 0x4000_2018, 0x4000_201C]
+    );
+    assert_eq!(RCC.addr_ctrl(), RCC.ptr_ctrl() as usize);
+    // any constant base works, not just the one given with `base = ...`
+    const OTHER: usize = Rcc::new(ConstantAddress::<0x5000_0000>).addr_enable(3);
+    assert_eq!(OTHER, 0x5000_001C);
+
+    // the base is a zero-sized type, so the block takes no space
+    assert_eq!(core::mem::size_of_val(&RCC), 0);
+}
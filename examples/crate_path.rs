@@ -0,0 +1,43 @@
+// a HAL re-exporting the crate under its own name, as vendored setups do
+mod hal {
+    pub use register_block as mmio;
+}
+
+use hal::mmio::{register_block, BaseAddress};
+
+#[register_block(crate = crate::hal::mmio)]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RW")]
+    #[bits(name = "enable", range = 0..1)]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO", count = 2)]
+    status: u32,
+    #[subblock(offset = 0x10, ty = Fifo)]
+    fifo: (),
+}
+
+#[register_block(flat, crate = hal::mmio)]
+pub struct Fifo {
+    #[register(offset = 0x00, access = "WO")]
+    data: u32,
+}
+
+// the derive takes the path too
+#[derive(Clone, Copy, BaseAddress)]
+#[base_address(crate = hal::mmio)]
+struct UartBase(usize);
+
+fn main() {
+    let mut buffer = [0u32; 5];
+    buffer[2] = 7;
+    let uart = Uart::new(buffer.as_mut_ptr() as usize);
+    uart.set_enable(true);
+    assert_eq!(uart.ctrl().read(), 1);
+    assert_eq!(uart.status(1).read(), 7);
+    uart.fifo().write_data(0x55);
+    assert_eq!(buffer[4], 0x55);
+    assert_eq!(Uart::<usize>::REGISTERS.len(), 2);
+
+    let uart = Uart::new(UartBase(buffer.as_mut_ptr() as usize));
+    assert_eq!(uart.status(1).read(), 7);
+}
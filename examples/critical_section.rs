@@ -0,0 +1,64 @@
+use std::cell::Cell;
+
+use register_block::{register_block, CriticalSection, RW};
+
+// a stand-in for e.g. `critical_section::with`, recording whether it's held
+thread_local! {
+    static INSIDE: Cell<bool> = const { Cell::new(false) };
+    static ENTERED: Cell<usize> = const { Cell::new(0) };
+}
+struct MockCs;
+impl CriticalSection for MockCs {
+    fn with<R>(f: impl FnOnce() -> R) -> R {
+        ENTERED.set(ENTERED.get() + 1);
+        INSIDE.set(true);
+        let result = f();
+        INSIDE.set(false);
+        result
+    }
+}
+
+#[register_block(flat)]
+pub struct Gpio {
+    // shared with the interrupt handler
+    #[register(offset = 0x00, access = "RW", cs = "MockCs")]
+    out: u32,
+    #[register(offset = 0x04, access = "RW")]
+    dir: u32,
+    // This should cause a compile error: only RW registers are read-modify-written
+    // #[register(offset = 0x08, access = "RO", cs = "MockCs")]
+    // input: u32,
+}
+
+#[register_block]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW", cs = "MockCs")]
+    irq_enable: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    let gpio = Gpio::new(buffer.as_mut_ptr() as usize);
+    gpio.write_out(0b01);
+    gpio.modify_out_cs(|v| {
+        assert!(INSIDE.get());
+        v | 0b10
+    });
+    assert!(!INSIDE.get());
+    assert_eq!(ENTERED.get(), 1);
+    assert_eq!(buffer[0], 0b11);
+
+    let timer = Timer::new(buffer.as_mut_ptr() as usize);
+    timer.modify_irq_enable_cs(|v| v & !0b01);
+    assert_eq!(ENTERED.get(), 2);
+    assert_eq!(buffer[0], 0b10);
+
+    // the wrappers take the critical section as a type parameter
+    let reg = unsafe { RW::<u32>::new(buffer.as_mut_ptr() as usize + 4) };
+    reg.modify_cs::<MockCs, _>(|v| {
+        assert!(INSIDE.get());
+        v + 1
+    });
+    assert_eq!(ENTERED.get(), 3);
+    assert_eq!(buffer[1], 1);
+}
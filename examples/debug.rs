@@ -0,0 +1,33 @@
+use register_block::register_block;
+
+#[register_block(derive_debug)]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u16,
+    // write-only and clear registers are skipped
+    #[register(offset = 0x08, access = "WO")]
+    data: u32,
+    #[register(offset = 0x0C, access = "WC")]
+    icr: u32,
+    // reading this would clear it, so it's skipped too
+    #[register(offset = 0x10, access = "RC")]
+    errors: u32,
+    #[register(offset = 0x14, access = "RO", count = 2)]
+    fifo: u8,
+}
+
+fn main() {
+    let mut buffer = [0u32; 6];
+    buffer[0] = 0x2A;
+    buffer[4] = 0xEE;
+    buffer[5] = u32::from_ne_bytes([0x1, 0x2, 0, 0]);
+    let uart = Uart::new(buffer.as_mut_ptr() as usize);
+    uart.ctrl().write(0x2A);
+
+    let dump = format!("{:?}", uart);
+    assert_eq!(dump, "Uart { ctrl: 0x2a, status: 0x0, fifo: [0x1, 0x2] }");
+    // RC registers are left out of the dump, so they keep their value
+    assert_eq!(uart.errors().read(), 0xEE);
+}
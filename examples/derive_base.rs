@@ -0,0 +1,42 @@
+use register_block::{register_block, BaseAddress};
+
+#[register_block]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RW")]
+    data: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u32,
+}
+
+// the base of a peripheral at a fixed address
+#[derive(Debug, Clone, Copy, BaseAddress)]
+#[base(0x4000_1000)]
+struct Uart1Base;
+
+// a base found at runtime, e.g. in the device tree
+#[derive(Debug, Clone, Copy, BaseAddress)]
+struct Uart2Base(usize);
+
+// forwarding works for any base, not just `usize`
+#[derive(Debug, Clone, Copy, BaseAddress)]
+struct Board<B: BaseAddress> {
+    base: B,
+}
+
+// This should cause a compile error: which field would be the base?
+// #[derive(Clone, Copy, BaseAddress)]
+// struct Ambiguous(usize, usize);
+
+fn main() {
+    assert_eq!(Uart1Base.base_address(), 0x4000_1000);
+    let uart1 = Uart::new(Uart1Base);
+    assert_eq!(uart1.ptr_status() as usize, 0x4000_1004);
+
+    let mut buffer = [0u32; 2];
+    let uart2 = Uart::new(Uart2Base(buffer.as_mut_ptr() as usize));
+    uart2.data().write(0x55);
+    assert_eq!(buffer[0], 0x55);
+
+    let board = Board { base: Uart1Base };
+    assert_eq!(board.base_address(), 0x4000_1000);
+}
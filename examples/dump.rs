@@ -0,0 +1,48 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct PortRegs {
+    #[register(offset = 0x00, access = "RW")]
+    mode: u16,
+    #[register(offset = 0x02, access = "RO")]
+    input: u16,
+}
+
+#[register_block]
+pub struct Gpio {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RC")]
+    events: u32,
+    #[register(offset = 0x08, access = "WO")]
+    set: u32,
+    #[register(offset = 0x10, access = "RO", count = 2)]
+    level: u32,
+    #[register(offset = 0x1C, access = "RW", endian = "big")]
+    id: u32,
+    #[subblock(offset = 0x20)]
+    port: PortRegs,
+}
+
+fn main() {
+    let mut buffer = [0u32; 9];
+    for (i, word) in buffer.iter_mut().enumerate() {
+        *word = 0x1111_1111 * (i as u32 + 1);
+    }
+    let gpio = Gpio::new(buffer.as_mut_ptr() as usize);
+    let image: [u8; 36] = unsafe { core::mem::transmute(buffer) };
+
+    let mut out = [0xEEu8; Gpio::<usize>::BLOCK_SIZE];
+    gpio.dump(&mut out);
+    for (offset, (dumped, expected)) in out.iter().zip(image).enumerate() {
+        match offset {
+            // `events` would clear on reading, `set` can't be read, 0x0C..0x10 and
+            // 0x18..0x1C are unmapped
+            0x04..0x10 | 0x18..0x1C => assert_eq!(*dumped, 0xEE),
+            // everything else, including the big-endian `id`, is copied as it is
+            _ => assert_eq!(*dumped, expected, "at offset {:#x}", offset),
+        }
+    }
+    // the dump didn't clear `events`
+    assert_eq!(buffer[1], 0x2222_2222);
+}
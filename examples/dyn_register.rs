@@ -0,0 +1,83 @@
+use register_block::{register_block, Register};
+
+#[register_block]
+pub struct Mixed {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u8,
+    #[register(offset = 0x02, access = "RW")]
+    divider: u16,
+    #[register(offset = 0x04, access = "RO")]
+    id: u32,
+    #[register(offset = 0x08, access = "RW", count = 2)]
+    timestamp: u64,
+    #[register(offset = 0x18, access = "WO")]
+    command: u32,
+    // left out of `registers_dyn()`, which only does plain aligned accesses
+    #[register(offset = 0x1D, access = "RW", unchecked)]
+    packed: u16,
+    #[register(offset = 0x1C, access = "RO", allow_any_type)]
+    flags: [u8; 1],
+}
+
+#[register_block]
+pub struct Wide {
+    #[register(offset = 0x00, access = "RW", endian = "big")]
+    ctrl: u32,
+    #[register(offset = 0x08, access = "RW", endian = "big")]
+    stamp: u64,
+    // left out, as it doesn't fit in a `u64`
+    #[register(offset = 0x10, access = "RW", width = 128)]
+    counter: u128,
+}
+
+fn main() {
+    let mut buffer = [0u64; 4];
+    let base = buffer.as_mut_ptr() as usize;
+    let block = Mixed::new(base);
+    block.timestamp(1).write(u64::MAX);
+    unsafe { ((base + 0x04) as *mut u32).write(0xC0FF_EE00) };
+
+    // one uniform list over registers of every width, arrays expanded
+    let registers: Vec<Box<dyn Register + '_>> = block.registers_dyn();
+    let names: Vec<_> = registers.iter().map(|reg| reg.name()).collect();
+    assert_eq!(
+        names,
+        ["ctrl", "divider", "id", "timestamp", "timestamp", "command"]
+    );
+    assert_eq!(registers[4].address(), base + 0x10);
+
+    for reg in &registers {
+        if reg.name() != "id" {
+            reg.write_u64(0x5A);
+        }
+    }
+    assert_eq!(block.ctrl().read(), 0x5A);
+    assert_eq!(block.divider().read(), 0x5A);
+    assert_eq!(block.timestamp(0).read(), 0x5A);
+    assert_eq!(block.timestamp(1).read(), 0x5A);
+
+    // narrower registers are zero-extended
+    assert_eq!(registers[2].read_u64(), 0xC0FF_EE00);
+    assert_eq!(registers[2].width(), 32);
+    // write-only registers read as 0, read-only ones ignore writes
+    assert_eq!(registers[5].read_u64(), 0);
+    registers[2].write_u64(0x1);
+    assert_eq!(block.id().read(), 0xC0FF_EE00);
+
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        registers[0].write_u64(0x100)
+    }))
+    .is_err());
+
+    // big-endian registers read and write native values, like their accessors
+    let mut buffer = [0u64; 4];
+    let wide = Wide::new(buffer.as_mut_ptr() as usize);
+    let registers = wide.registers_dyn();
+    assert_eq!(registers.len(), 2);
+    wide.ctrl().write(0x1122_3344);
+    assert_eq!(registers[0].read_u64(), 0x1122_3344);
+    registers[1].write_u64(0x0102_0304_0506_0708);
+    assert_eq!(wide.stamp().read(), 0x0102_0304_0506_0708);
+    assert_eq!(buffer[1], 0x0102_0304_0506_0708u64.to_be());
+}
@@ -0,0 +1,40 @@
+use core::cell::Cell;
+use register_block::{register_block, DynamicBase};
+
+#[register_block]
+pub struct Window {
+    #[register(offset = 0x00, access = "RW")]
+    data: u32,
+    #[register(offset = 0x04, access = "RO")]
+    id: u32,
+}
+
+fn main() {
+    let mut pages = [[0u32; 2]; 2];
+    pages[0][1] = 0xA;
+    pages[1][1] = 0xB;
+    let first = pages.as_mut_ptr() as usize;
+    let page_size = core::mem::size_of::<[u32; 2]>();
+
+    // the page the window shows, and how often the base was asked for
+    let page = Cell::new(0);
+    let calls = Cell::new(0);
+    let base = || {
+        calls.set(calls.get() + 1);
+        first + page.get() * page_size
+    };
+    let window = Window::new(DynamicBase::new(&base));
+
+    assert_eq!(window.id().read(), 0xA);
+    window.data().write(1);
+    page.set(1);
+    assert_eq!(window.id().read(), 0xB);
+    window.data().modify(|data| data + 2);
+    assert_eq!(pages[0][0], 1);
+    assert_eq!(pages[1][0], 2);
+
+    // the closure runs once per accessor call
+    assert_eq!(calls.get(), 4);
+    assert_eq!(window.base_address(), first + page_size);
+    assert_eq!(calls.get(), 5);
+}
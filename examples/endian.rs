@@ -0,0 +1,51 @@
+use register_block::{register_block, BE, RW};
+
+#[register_block]
+pub struct NetRegs {
+    #[register(offset = 0x00, access = "RW", endian = "big")]
+    #[bits(name = "enable", range = 0..1)]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO", endian = "big")]
+    id: u16,
+    #[register(offset = 0x08, access = "RW")]
+    native: u32,
+    #[register(offset = 0x0C, access = "RW", endian = "big")]
+    temperature: i32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 4];
+    let base = buffer.as_mut_ptr() as usize;
+    let regs = NetRegs::new(base);
+
+    regs.ctrl().write(0x1122_3344);
+    regs.native().write(0x1122_3344);
+    let bytes = |offset: usize| unsafe { ((base + offset) as *const [u8; 4]).read() };
+    // the big-endian register stores its most significant byte first, on any host
+    assert_eq!(bytes(0x00), [0x11, 0x22, 0x33, 0x44]);
+    assert_eq!(bytes(0x08), 0x1122_3344u32.to_ne_bytes());
+    assert_eq!(regs.ctrl().read(), 0x1122_3344);
+
+    regs.ctrl().write(0);
+    regs.set_enable(true);
+    assert_eq!(bytes(0x00), [0x00, 0x00, 0x00, 0x01]);
+    assert!(regs.enable());
+
+    unsafe { ((base + 0x04) as *mut [u8; 2]).write([0xAB, 0xCD]) };
+    assert_eq!(regs.id().read(), 0xABCD);
+
+    // signed registers swap their two's complement pattern
+    regs.temperature().write(-2);
+    assert_eq!(bytes(0x0C), [0xFF, 0xFF, 0xFF, 0xFE]);
+    assert_eq!(regs.temperature().read(), -2);
+    regs.temperature().modify(|v| v * 3);
+    assert_eq!(regs.temperature().read(), -6);
+
+    // the wrappers can be used on their own as well
+    let raw = BE::new(unsafe { RW::<u16>::new(base + 0x08) });
+    raw.write(0x0102);
+    assert_eq!(
+        unsafe { ((base + 0x08) as *const [u8; 2]).read() },
+        [0x01, 0x02]
+    );
+}
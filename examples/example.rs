@@ -1,10 +1,15 @@
-use register_block::register_block;
+use register_block::{register_block, RO, RW, WC};
+
+// Wrappers can be built in const contexts for statically-known addresses
+const UART_DR: RW<'static, u32> = unsafe { RW::new(0x1000_0000) };
+const UART_FR: RO<'static, u32> = unsafe { RO::new(0x1000_0018) };
+const UART_ICR: WC<'static, u32> = unsafe { WC::with_clear_value(0x1000_0044, 0x7FF) };
 
 #[register_block]
 pub struct TestRegs {
     #[register(offset = 0x00, access = "RW")]
     reg0: u32,
-    #[register(offset = 0x04, access = "RO")]
+    #[register(offset = 0x04, access = "RO", width = 32)]
     reg1: u32,
     #[register(offset = 0x08, access = "WO")]
     reg2: u32,
@@ -13,17 +18,105 @@ pub struct TestRegs {
     reg2_ro: u32,
     #[register(offset = 0x0C, access = "WC")]
     reg3: u32,
+    #[register(offset = 0x10, access = "RC")]
+    reg4: u32,
+    // This should be allowed: RC overlaps with WC
+    #[register(offset = 0x0C, access = "RC")]
+    reg3_rc: u32,
     // This should cause a compile error: RW overlaps with RW
     // #[register(offset = 0x00, access = "RW")]
     // reg0_dup: u32,
     // This should cause a compile error: WO overlaps with RW
     // #[register(offset = 0x00, access = "WO")]
     // reg0_wo: u32,
+    // This should cause a compile error: width doesn't match the field type
+    // #[register(offset = 0x14, access = "RW", width = 16)]
+    // reg5: u32,
+    // This should cause a compile error pointing at the attribute: `access` is missing
+    // #[register(offset = 0x14)]
+    // reg6: u32,
+    // This should cause a compile error pointing at "RWX": unknown access type
+    // #[register(offset = 0x14, access = "RWX")]
+    // reg7: u32,
+    // This should cause a compile error pointing at the field: no #[register] at all
+    // reg8: u32,
+    // This should cause a compile error: RC overlaps with RO
+    // #[register(offset = 0x04, access = "RC")]
+    // reg1_rc: u32,
+    // This should cause a compile error: the u8 lies inside the bytes of `reg1`
+    // #[register(offset = 0x06, access = "RW")]
+    // reg1_byte: u8,
+    // This should cause a compile error: RO may only overlap WO if they cover the same bytes
+    // #[register(offset = 0x08, access = "RO")]
+    // reg2_half: u16,
+    // This should cause a compile error: arrays aren't primitive integers
+    // #[register(offset = 0x14, access = "RW")]
+    // bytes: [u8; 3],
+    // This should cause a compile error: neither are tuples or structs
+    // #[register(offset = 0x14, access = "RW")]
+    // pair: (u16, u16),
+}
+
+// Other types need an explicit opt-in, and must still be plain data (see `MmioPrimitive`)
+#[register_block]
+pub struct RawRegs {
+    #[register(offset = 0x00, access = "RO", allow_any_type)]
+    id: [u8; 4],
+    // This should cause a compile error: `String` can't be accessed with volatile MMIO
+    // #[register(offset = 0x04, access = "RO", allow_any_type)]
+    // name: String,
+}
+
+// Registers don't need to be contiguous
+#[register_block]
+pub struct GappedRegs {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u8,
+    #[register(offset = 0x20, access = "RW")]
+    data: u16,
+    #[register(offset = 0x08, access = "RO", count = 3, stride = 8)]
+    status: u32,
+}
+
+// Offsets accept any integer literal form
+#[register_block]
+pub struct LiteralRegs {
+    #[register(offset = 0x1000, access = "RW")]
+    hex: u32,
+    #[register(offset = 0b1010_0000, access = "RW")]
+    binary: u32,
+    #[register(offset = 0o20, access = "RW")]
+    octal: u32,
+    #[register(offset = 1_024, access = "RW")]
+    decimal: u32,
+    // Keys may come in any order
+    #[register(access = "RW", offset = 0x2000)]
+    reordered: u32,
+    // This should cause compile errors pointing at "ofset" and "widht", listing the valid keys
+    // #[register(ofset = 0x10, access = "RW", widht = 32)]
+    // misspelled: u32,
+    // This should cause a compile error: the offset is not an integer literal
+    // #[register(offset = "0x10", access = "RW")]
+    // string: u32,
+    // This should cause a compile error: the offset doesn't fit in 32 bits
+    // #[register(offset = 0x1_0000_0000, access = "RW")]
+    // too_large: u32,
 }
 
 fn main() {
-    let buffer = [0u8; 0x10];
-    let regs = TestRegs::new(&buffer[0] as *const u8 as usize);
+    // the const wrappers above point at a real UART, so only construct them here
+    let _ = (UART_DR, UART_FR, UART_ICR);
+    assert_eq!(LiteralRegs::<usize>::HEX_OFFSET, 0x1000);
+    assert_eq!(LiteralRegs::<usize>::BINARY_OFFSET, 160);
+    assert_eq!(LiteralRegs::<usize>::OCTAL_OFFSET, 16);
+    assert_eq!(LiteralRegs::<usize>::DECIMAL_OFFSET, 1024);
+    assert_eq!(LiteralRegs::<usize>::REORDERED_OFFSET, 0x2000);
+    assert_eq!(TestRegs::<usize>::BLOCK_SIZE, 0x14);
+    // the last `status` entry ends at 0x08 + 2 * 8 + 4 = 0x1C, `data` ends at 0x22
+    assert_eq!(GappedRegs::<usize>::BLOCK_SIZE, 0x22);
+
+    let mut buffer = [0u32; 0x14 / 4];
+    let regs = TestRegs::new(buffer.as_mut_ptr().cast::<u8>());
     // The following methods should exist:
     let _ = regs.reg0().read();
     let _ = regs.reg1().read();
@@ -31,4 +124,55 @@ fn main() {
     regs.reg2().write(1);
     regs.reg3().clear();
     let _ = regs.reg2_ro().read();
+    let _ = regs.reg4().read();
+    let _ = regs.reg3_rc().read();
+
+    // raw pointers for handing registers to DMA or FFI
+    let base = buffer.as_ptr() as usize;
+    assert_eq!(regs.ptr_reg0() as usize, base);
+    let reg1: *const u32 = regs.ptr_reg1();
+    assert_eq!(reg1 as usize, base + 0x04);
+    assert_eq!(unsafe { regs.ptr_reg0().read() }, 42);
+
+    let raw = RawRegs::new(buffer.as_ptr().cast::<u8>());
+    assert_eq!(raw.id().read(), 42u32.to_ne_bytes());
+
+    // `{:p}` shows the address a wrapper targets
+    assert_eq!(format!("{:p}", regs.reg1()), format!("{:#x}", base + 0x04));
+    let fixed = unsafe { RW::<u32>::new(0x4000_1000) };
+    // This should cause a compile error: the wrappers only take integers and arrays of them
+    // let _ = unsafe { RW::<String>::new(0x4000_1000) };
+    assert_eq!(format!("{:p}", fixed), "0x40001000");
+
+    // shorthands for the usual "I have a pointer" and "I have an address" cases
+    let from_ptr = unsafe { TestRegs::from_ptr(buffer.as_ptr().cast::<u8>()) };
+    let from_addr = unsafe { TestRegs::from_addr(buffer.as_ptr() as usize) };
+    assert_eq!(from_ptr.reg0().read(), 42);
+    assert_eq!(from_addr.reg0().read(), 42);
+
+    // any of the common pointer types can serve as the base address
+    let by_const_ptr = TestRegs::new(buffer.as_ptr().cast::<u8>());
+    let by_non_null = TestRegs::new(core::ptr::NonNull::from(&mut buffer).cast::<u8>());
+    assert_eq!(by_const_ptr.reg0().read(), 42);
+    assert_eq!(by_non_null.reg0().read(), 42);
+
+    // registers can also be reached by offset, e.g. undocumented ones
+    assert_eq!(unsafe { regs.peek::<u32>(0x00) }, regs.reg0().read());
+    unsafe { regs.poke::<u16>(0x10, 0xBEEF) };
+    assert_eq!(regs.reg4().read() & 0xFFFF, 0xBEEF);
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| unsafe { regs.peek::<u32>(0x14) }).is_err());
+
+    // fallible updates only write when the closure accepts the new value
+    let limited = |v: u32| if v < 100 { Ok(v + 50) } else { Err(v) };
+    assert_eq!(regs.reg0().try_modify(limited), Ok(()));
+    assert_eq!(buffer[0], 92);
+    assert_eq!(regs.reg0().try_modify(limited), Ok(()));
+    assert_eq!(buffer[0], 142);
+    assert_eq!(regs.reg0().try_modify(limited), Err(142));
+    assert_eq!(buffer[0], 142);
+
+    // misaligned register addresses are caught in debug builds
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| unsafe { RW::<u32>::new(0x1001) }).is_err());
 }
@@ -0,0 +1,55 @@
+use register_block::{register_block, BaseToken};
+
+#[register_block(exclusive)]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RW")]
+    data: u32,
+}
+
+#[register_block(base = 0x4000_2000, exclusive)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RO")]
+    count: u32,
+}
+
+// This should cause a compile error: building an exclusive block without a token is unsafe
+// fn alias() -> Uart<usize> {
+//     Uart::new(0x4000_1000)
+// }
+
+// This should cause a compile error: a token can't be copied into a second block
+// fn alias(token: BaseToken<0x4000_1000>) {
+//     let first = Uart::from_token(token);
+//     let second = Uart::from_token(token);
+// }
+
+fn main() {
+    // every base hands out a single token
+    let token = BaseToken::<0x4000_1000>::take().unwrap();
+    assert!(BaseToken::<0x4000_1000>::take().is_none());
+    assert_eq!(BaseToken::<0x4000_1000>::ADDR, 0x4000_1000);
+    let uart = Uart::from_token(token);
+    assert_eq!(uart.base_address(), 0x4000_1000);
+    // consuming the token doesn't give the base back
+    assert!(BaseToken::<0x4000_1000>::take().is_none());
+
+    // other bases are unaffected
+    assert!(BaseToken::<0x4000_1100>::take().is_some());
+
+    // This should cause a compile error: the table marks free slots with `usize::MAX`
+    // let _ = BaseToken::<{ usize::MAX }>::take();
+
+    // with a fixed base, the block takes its own token
+    let timer = Timer::take().unwrap();
+    assert_eq!(timer.base_address(), Timer::BASE);
+    assert!(Timer::take().is_none());
+    assert!(BaseToken::<0x4000_2000>::take().is_none());
+
+    // the unsafe constructors remain for bases known only at runtime
+    let mut buffer = [0u32; 1];
+    let uart = unsafe { Uart::new(buffer.as_mut_ptr() as usize) };
+    uart.data().write(0x55);
+    assert_eq!(buffer[0], 0x55);
+    let uart = unsafe { Uart::from_ptr(buffer.as_ptr().cast()) };
+    assert_eq!(uart.data().read(), 0x55);
+}
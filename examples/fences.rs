@@ -0,0 +1,75 @@
+use core::sync::atomic::Ordering;
+
+use register_block::register_block;
+
+#[register_block]
+pub struct Rx {
+    #[register(offset = 0x00, access = "RO")]
+    status: u32,
+    #[register(offset = 0x04, access = "RO")]
+    data: u32,
+    #[register(offset = 0x08, access = "WO")]
+    doorbell: u32,
+    #[register(offset = 0x0C, access = "RW")]
+    ctrl: u32,
+}
+
+fn main() {
+    let mut buffer = [1u32, 0xDA7A, 0, 0];
+    let base = buffer.as_mut_ptr() as usize;
+    let rx = Rx::new(base);
+
+    // the fence after reading `status` keeps the `data` read from moving ahead of it
+    if rx.status().read_acquire() & 1 != 0 {
+        assert_eq!(rx.data().read(), 0xDA7A);
+    }
+    rx.doorbell().write_release(1);
+    rx.ctrl().write_release(0x10);
+    assert_eq!(rx.ctrl().read_acquire(), 0x10);
+    assert_eq!(unsafe { ((base + 0x08) as *const u32).read() }, 1);
+
+    // configure, strobe and read back as one sequence
+    let mut ran = false;
+    let ctrl = rx.transaction(|rx| {
+        rx.ctrl().write(0x20);
+        rx.doorbell().write(2);
+        ran = true;
+        rx.ctrl().read()
+    });
+    assert!(ran);
+    assert_eq!(ctrl, 0x20);
+    assert_eq!(unsafe { ((base + 0x08) as *const u32).read() }, 2);
+
+    // the ordering picks the fences; the access itself is the same for every valid one
+    for (value, order) in [
+        (0x30, Ordering::Relaxed),
+        (0x31, Ordering::Release),
+        (0x32, Ordering::SeqCst),
+    ] {
+        rx.ctrl().write_ordered(value, order);
+        assert_eq!(unsafe { ((base + 0x0C) as *const u32).read() }, value);
+    }
+    for order in [Ordering::Relaxed, Ordering::Acquire, Ordering::SeqCst] {
+        assert_eq!(rx.ctrl().read_ordered(order), 0x32);
+        assert_eq!(rx.data().read_ordered(order), 0xDA7A);
+    }
+    rx.doorbell().write_ordered(3, Ordering::SeqCst);
+    assert_eq!(unsafe { ((base + 0x08) as *const u32).read() }, 3);
+
+    // orderings that don't apply to the access are caught in debug builds, and still
+    // perform the access (fenced like `SeqCst`) in release builds
+    if cfg!(debug_assertions) {
+        let rx = &rx;
+        assert!(std::panic::catch_unwind(|| rx.ctrl().read_ordered(Ordering::AcqRel)).is_err());
+        assert!(std::panic::catch_unwind(|| rx.status().read_ordered(Ordering::Release)).is_err());
+        assert!(
+            std::panic::catch_unwind(|| rx.ctrl().write_ordered(0x40, Ordering::AcqRel)).is_err()
+        );
+        assert!(
+            std::panic::catch_unwind(|| rx.doorbell().write_ordered(4, Ordering::Acquire)).is_err()
+        );
+    } else {
+        rx.ctrl().write_ordered(0x40, Ordering::AcqRel);
+        assert_eq!(rx.ctrl().read_ordered(Ordering::AcqRel), 0x40);
+    }
+}
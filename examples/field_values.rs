@@ -0,0 +1,46 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    #[field(name = "mode", range = 0..2, values = [
+        ("Disabled", 0, "Counter stopped"),
+        ("OneShot", 1, "Stops after the first overflow"),
+        ("Periodic", 2),
+    ])]
+    #[field(name = "clock_source", range = 4..5, ty = ClockSource, values = [("Internal", 0), ("External", 1)])]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    #[field(name = "state", range = 0..8, values = [("Idle", 0), ("Running", 0xFF)])]
+    status: u32,
+    // This should cause a compile error: 4 doesn't fit in two bits
+    // #[register(offset = 0x08, access = "RW")]
+    // #[field(name = "prescaler", range = 0..2, values = [("Div1", 0), ("Div16", 4)])]
+    // prescale: u32,
+    // This should cause a compile error: `Unknown` is taken by the values without a name
+    // #[register(offset = 0x08, access = "RW")]
+    // #[field(name = "edge", range = 0..2, values = [("Rising", 0), ("Unknown", 3)])]
+    // capture: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    buffer[1] = 0x17;
+    let timer = Timer::new(buffer.as_mut_ptr() as usize);
+
+    assert_eq!(timer.mode(), Mode::Disabled);
+    timer.set_mode(Mode::Periodic);
+    assert_eq!(timer.mode(), Mode::Periodic);
+    assert_eq!(buffer[0], 0b10);
+    timer.set_clock_source(ClockSource::External);
+    assert_eq!(timer.clock_source(), ClockSource::External);
+    assert_eq!(buffer[0], 0b1_0010);
+
+    // values without a name round-trip through `Unknown`
+    timer.set_mode(Mode::Unknown(3));
+    assert_eq!(timer.mode(), Mode::Unknown(3));
+    assert_eq!(buffer[0], 0b1_0011);
+    assert_eq!(timer.state(), State::Unknown(0x17));
+    assert_eq!(u8::from(State::Running), 0xFF);
+    assert_eq!(Mode::from(1u8), Mode::OneShot);
+}
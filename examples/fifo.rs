@@ -0,0 +1,67 @@
+use register_block::mock::{self, MmioBackend, MockAccess, VecBackend};
+use register_block::register_block;
+
+#[register_block]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RC", fifo = true)]
+    rx: u8,
+    #[register(offset = 0x04, access = "WO", fifo = true)]
+    tx: u8,
+    #[register(offset = 0x08, access = "RO")]
+    level: u32,
+    // This should cause a compile error: a FIFO port is a single address
+    // #[register(offset = 0x10, access = "RO", count = 4, fifo = true)]
+    // window: u32,
+}
+
+// a receive queue behind a single data port, popping an entry on every read
+struct RxQueue {
+    queue: Vec<u8>,
+    memory: VecBackend,
+}
+
+impl MmioBackend for RxQueue {
+    fn read(&mut self, address: usize, bytes: &mut [u8]) {
+        if address == 0x4000_0000 {
+            bytes[0] = self.queue.remove(0);
+        } else {
+            self.memory.read(address, bytes)
+        }
+    }
+    fn write(&mut self, address: usize, bytes: &[u8]) {
+        self.memory.write(address, bytes)
+    }
+}
+
+fn main() {
+    let memory = VecBackend::new(0x4000_0000, 0x0C);
+    mock::install(RxQueue {
+        queue: b"hello".to_vec(),
+        memory: memory.clone(),
+    });
+    let uart = Uart::new(0x4000_0000);
+
+    let mut received = [0u8; 3];
+    uart.drain_rx(&mut received);
+    assert_eq!(&received, b"hel");
+    let mut rest = [0u8; 2];
+    uart.drain_rx(&mut rest);
+    assert_eq!(&rest, b"lo");
+
+    // every entry goes to the same address, in order
+    uart.fill_tx(b"ok");
+    assert_eq!(
+        memory.log(),
+        [
+            MockAccess::Write {
+                address: 0x4000_0004,
+                value: u128::from(b'o'),
+            },
+            MockAccess::Write {
+                address: 0x4000_0004,
+                value: u128::from(b'k'),
+            },
+        ]
+    );
+    mock::uninstall();
+}
@@ -0,0 +1,85 @@
+use register_block::register_block;
+
+#[register_block(flat)]
+pub struct FlatRegs {
+    #[register(offset = 0x00, access = "RW")]
+    reg0: u32,
+    #[register(offset = 0x04, access = "RO")]
+    reg1: u32,
+    #[register(offset = 0x08, access = "WO")]
+    reg2: u32,
+    #[register(offset = 0x0C, access = "WC")]
+    reg3: u32,
+    #[register(offset = 0x0C, access = "RC")]
+    reg3_rc: u32,
+}
+
+// Same layout, using the default wrapper-returning accessors
+#[register_block]
+pub struct WrappedRegs {
+    #[register(offset = 0x00, access = "RW")]
+    reg0: u32,
+    #[register(offset = 0x04, access = "RO")]
+    reg1: u32,
+    #[register(offset = 0x08, access = "WO")]
+    reg2: u32,
+    #[register(offset = 0x0C, access = "WC")]
+    reg3: u32,
+    #[register(offset = 0x0C, access = "RC")]
+    reg3_rc: u32,
+}
+
+fn buffer_word(base: usize, index: usize) -> u32 {
+    unsafe { (base as *const u32).add(index).read_volatile() }
+}
+
+fn main() {
+    let mut buffer = [0u32; 4];
+    buffer[1] = 7;
+    buffer[3] = 0xFF;
+    let base = buffer.as_mut_ptr() as usize;
+    let flat = FlatRegs::new(base);
+    let wrapped = WrappedRegs::new(base);
+
+    flat.write_reg0(42);
+    assert_eq!(wrapped.reg0().read(), 42);
+    wrapped.reg0().write(44);
+    assert_eq!(flat.read_reg0(), 44);
+
+    // a single read-modify-write of reg0
+    flat.modify_reg0(|v| v | 1);
+    assert_eq!(buffer_word(base, 0), 45);
+    flat.modify_reg0(|v| v << 4);
+    assert_eq!(buffer_word(base, 0), 45 << 4);
+
+    // a masked write leaves the bits outside the mask alone
+    flat.write_reg0(0xAAAA_AAAA);
+    flat.write_reg0_masked(0x5555_5555, 0x0000_FFFF);
+    assert_eq!(buffer_word(base, 0), 0xAAAA_5555);
+
+    assert_eq!(flat.read_reg1(), wrapped.reg1().read());
+
+    flat.write_reg2(1);
+    assert_eq!(buffer_word(base, 2), 1);
+    wrapped.reg2().write(2);
+    assert_eq!(buffer_word(base, 2), 2);
+
+    assert_eq!(flat.read_reg3_rc(), 0xFF);
+    flat.clear_reg3();
+    assert_eq!(wrapped.reg3_rc().read(), 0);
+
+    // modify is exactly one read and one write
+    #[cfg(feature = "mock")]
+    {
+        use register_block::mock::{self, VecBackend};
+
+        let backend = VecBackend::new(0x1000, 0x10);
+        mock::install(backend.clone());
+        let flat = FlatRegs::new(0x1000);
+        flat.modify_reg0(|v| v | 1);
+        assert_eq!(backend.read_count(0x00), 1);
+        assert_eq!(backend.write_count(0x00), 1);
+        assert_eq!(backend.memory()[..4], 1u32.to_ne_bytes());
+        mock::uninstall();
+    }
+}
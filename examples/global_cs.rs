@@ -0,0 +1,50 @@
+use std::cell::Cell;
+
+use register_block::{register_block, GlobalCs, RW};
+
+// a stand-in for the target's `critical-section` implementation, recording whether it's held
+thread_local! {
+    static INSIDE: Cell<bool> = const { Cell::new(false) };
+    static ACQUIRED: Cell<usize> = const { Cell::new(0) };
+}
+struct MockImpl;
+critical_section::set_impl!(MockImpl);
+unsafe impl critical_section::Impl for MockImpl {
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        assert!(!INSIDE.get());
+        ACQUIRED.set(ACQUIRED.get() + 1);
+        INSIDE.set(true);
+    }
+
+    unsafe fn release(_: critical_section::RawRestoreState) {
+        INSIDE.set(false);
+    }
+}
+
+#[register_block]
+pub struct Gpio {
+    #[register(offset = 0x00, access = "RW", cs = "register_block::GlobalCs")]
+    out: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    let gpio = Gpio::new(buffer.as_mut_ptr() as usize);
+    gpio.out().write(0b01);
+    gpio.modify_out_cs(|v| {
+        assert!(INSIDE.get());
+        v | 0b10
+    });
+    assert!(!INSIDE.get());
+    assert_eq!(ACQUIRED.get(), 1);
+    assert_eq!(buffer[0], 0b11);
+
+    let reg = unsafe { RW::<u32>::new(buffer.as_mut_ptr() as usize + 4) };
+    reg.modify_cs::<GlobalCs, _>(|v| {
+        assert!(INSIDE.get());
+        v + 1
+    });
+    assert!(!INSIDE.get());
+    assert_eq!(ACQUIRED.get(), 2);
+    assert_eq!(buffer[1], 1);
+}
@@ -0,0 +1,49 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct UartTx {
+    #[register(offset = 0x00, access = "WO")]
+    data: u32,
+    #[register(offset = 0x04, access = "RO")]
+    level: u32,
+}
+
+#[register_block]
+pub struct UartRx {
+    #[register(offset = 0x08, access = "RO")]
+    data: u32,
+    #[register(offset = 0x0C, access = "RO")]
+    level: u32,
+}
+
+#[register_block]
+pub struct UartControl {
+    #[register(offset = 0x10, access = "RW")]
+    baud: u32,
+    #[register(offset = 0x14, access = "RW")]
+    enable: u32,
+}
+
+/// One UART, split into groups that all use the same base address
+#[register_block(group)]
+pub struct Uart {
+    tx: UartTx,
+    rx: UartRx,
+    control: UartControl,
+}
+
+fn main() {
+    let mut buffer = [0u32; 6];
+    buffer[2] = 0x41;
+    let base = buffer.as_mut_ptr() as usize;
+    let uart = Uart::new(base);
+
+    uart.control().baud().write(115_200);
+    uart.control().enable().write(1);
+    uart.tx().data().write(0x42);
+    assert_eq!(uart.rx().data().read(), 0x41);
+    assert_eq!(buffer, [0x42, 0, 0x41, 0, 115_200, 1]);
+
+    let _: UartTx<usize> = Uart::new(base).tx();
+    assert_eq!(Uart::<usize>::BLOCK_SIZE, 0x18);
+}
@@ -0,0 +1,24 @@
+use register_block::register_block;
+
+#[register_block]
+#[init(ctrl = 0, baud = 48_000 / 9600, ctrl = 0x1 | 0x8)]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "WO")]
+    baud: u16,
+    #[register(offset = 0x08, access = "RO")]
+    status: u32,
+    // This should cause a compile error: adding `status = 1` to #[init], RO can't be written
+}
+
+fn main() {
+    let mut buffer = [0xFFFF_FFFFu32; 3];
+    let base = buffer.as_mut_ptr() as usize;
+    let uart = Uart::new(base);
+
+    uart.apply_init();
+    assert_eq!(uart.ctrl().read(), 0x9);
+    assert_eq!(unsafe { ((base + 0x04) as *const u16).read() }, 5);
+    assert_eq!(uart.status().read(), 0xFFFF_FFFF);
+}
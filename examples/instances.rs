@@ -0,0 +1,32 @@
+use register_block::register_block;
+
+// three identical timers, 0x10 bytes apart
+#[register_block(flat, instances = 3, stride = 0x10)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RW")]
+    load: u32,
+    #[register(offset = 0x08, access = "RO")]
+    count: u32,
+}
+// This should cause a compile error: `stride = 0x08` is smaller than the block
+
+fn main() {
+    let mut buffer = [0u32; 12];
+    let base = buffer.as_mut_ptr() as usize;
+    let timers = Timer::new(base);
+    assert_eq!(Timer::<usize>::INSTANCES, 3);
+    assert_eq!(Timer::<usize>::INSTANCE_STRIDE, 0x10);
+
+    for i in 0..Timer::<usize>::INSTANCES {
+        timers.instance(i).write_load(100 * (i as u32 + 1));
+    }
+    timers.instance(2).write_ctrl(1);
+    assert_eq!(buffer, [0, 100, 0, 0, 0, 200, 0, 0, 1, 300, 0, 0]);
+    assert_eq!(timers.instance(1).base_address(), base + 0x10);
+    assert_eq!(timers.instance(1).read_load(), 200);
+
+    // out-of-range instances are caught, in release builds too
+    assert!(std::panic::catch_unwind(|| timers.instance(3)).is_err());
+}
@@ -0,0 +1,55 @@
+use register_block::mock::{self, MockAccess, VecBackend};
+use register_block::register_block;
+
+#[register_block(flat)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    // writing 1 copies the running counter into `value`
+    #[register(offset = 0x04, access = "WO")]
+    capture: u32,
+    #[register(offset = 0x08, access = "RO", latch_via = "capture")]
+    value: u32,
+    // triggers can take any value, here bit 1 latches the overflow count
+    #[register(offset = 0x0C, access = "RO", latch_via = "ctrl", latch_value = 0b10)]
+    overflows: u16,
+    // This should cause a compile error: RO registers can't be written to trigger a latch
+    // #[register(offset = 0x10, access = "RO", latch_via = "value")]
+    // stale: u32,
+    // This should cause a compile error: there's no register called `captur`
+    // #[register(offset = 0x14, access = "RO", latch_via = "captur")]
+    // typo: u32,
+}
+
+fn main() {
+    let backend = VecBackend::new(0x4000_0000, 0x10);
+    backend.set(0x4000_0008, &1234u32.to_ne_bytes());
+    mock::install(backend.clone());
+    let timer = Timer::new(0x4000_0000);
+
+    assert_eq!(timer.read_value_latched(), 1234);
+    assert_eq!(timer.read_overflows_latched(), 0);
+    // the trigger is written before the register is read
+    assert_eq!(
+        backend.log(),
+        [
+            MockAccess::Write {
+                address: 0x4000_0004,
+                value: 1,
+            },
+            MockAccess::Read {
+                address: 0x4000_0008,
+                value: 1234,
+            },
+            MockAccess::Write {
+                address: 0x4000_0000,
+                value: 0b10,
+            },
+            MockAccess::Read {
+                address: 0x4000_000C,
+                value: 0,
+            },
+        ]
+    );
+    mock::uninstall();
+}
@@ -0,0 +1,80 @@
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use register_block::{register_block, RawLock};
+
+#[register_block(lock)]
+pub struct Dma {
+    #[register(offset = 0x00, access = "RW")]
+    busy: u32,
+    #[register(offset = 0x04, access = "RW")]
+    count: u32,
+}
+
+// a lock that counts how often it was taken
+pub struct Counted(AtomicBool);
+static LOCKS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl RawLock for Counted {
+    const INIT: Self = Counted(AtomicBool::new(false));
+    fn lock(&self) {
+        while self.0.swap(true, Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+        LOCKS.fetch_add(1, Ordering::Relaxed);
+    }
+    unsafe fn unlock(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+#[register_block(lock = Counted)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    load: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    let dma = Dma::new(buffer.as_mut_ptr() as usize);
+
+    // two threads running the same read-modify-write sequence never overlap, and lose no
+    // updates
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    dma.locked(|dma| {
+                        assert_eq!(dma.busy().read(), 0, "two sequences overlapped");
+                        dma.busy().write(1);
+                        let count = dma.count().read();
+                        std::thread::yield_now();
+                        dma.count().write(count + 1);
+                        dma.busy().write(0);
+                    });
+                }
+            });
+        }
+    });
+    assert_eq!(buffer, [0, 2000]);
+
+    // a panicking sequence releases the lock
+    let result = std::panic::catch_unwind(|| dma.locked(|_| panic!("sequence failed")));
+    assert!(result.is_err());
+    assert_eq!(dma.locked(|dma| dma.count().read()), 2000);
+
+    // the lock is the block's own: a second block at the same base doesn't wait for it, which
+    // is why there should only be one per peripheral
+    let other = Dma::new(buffer.as_mut_ptr() as usize);
+    dma.locked(|_| other.locked(|other| other.count().read()));
+
+    // user-provided locks
+    let mut load = [0u32; 1];
+    let timer = Timer::new(load.as_mut_ptr() as usize);
+    let previous = timer.locked(|timer| {
+        let previous = timer.load().read();
+        timer.load().write(100);
+        previous
+    });
+    assert_eq!(previous, 0);
+    assert_eq!(load[0], 100);
+    assert_eq!(LOCKS.load(Ordering::Relaxed), 1);
+}
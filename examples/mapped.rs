@@ -0,0 +1,48 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Gpio {
+    #[register(offset = 0x00, access = "RW")]
+    dir: u32,
+    #[register(offset = 0x04, access = "RO")]
+    input: u32,
+}
+
+#[cfg(target_os = "linux")]
+fn main() {
+    use register_block::MappedBlock;
+    use std::fs::OpenOptions;
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    // a file stands in for /dev/mem, with the registers 0x10 bytes into the second page
+    let path = std::env::temp_dir().join(format!("register-block-mapped-{}", std::process::id()));
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    let offset = 0x1010;
+    file.set_len(0x2000).unwrap();
+    file.seek(SeekFrom::Start(offset + 4)).unwrap();
+    file.write_all(&0xA5u32.to_ne_bytes()).unwrap();
+
+    {
+        let gpio = unsafe { MappedBlock::map(&file, offset as usize, 8, Gpio::new) }.unwrap();
+        assert_eq!(gpio.input().read(), 0xA5);
+        gpio.dir().write(0x0F);
+    }
+    // unmapped again, and the write went through to the file
+    let mut dir = [0u8; 4];
+    file.seek(SeekFrom::Start(offset)).unwrap();
+    file.read_exact(&mut dir).unwrap();
+    assert_eq!(u32::from_ne_bytes(dir), 0x0F);
+
+    // the mapping must be large enough for the block
+    assert!(unsafe { MappedBlock::map(&file, 0, 4, Gpio::new) }.is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {}
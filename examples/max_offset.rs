@@ -0,0 +1,55 @@
+use register_block::register_block;
+
+// a peripheral decoding only 8 address bits
+#[register_block(max_offset = 0xFF)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x10, access = "RW", count = 4)]
+    compare: u32,
+    #[reserved(offset = 0x20, len = 0xD0)]
+    _gap: (),
+    // the last byte of this register sits exactly at max_offset
+    #[register(offset = 0xFC, access = "RO")]
+    counter: u32,
+    // This should cause a compile error: the register spans 0x100..0x104, past 0xFF
+    // #[register(offset = 0x100, access = "RW")]
+    // typo: u32,
+    // This should cause a compile error: the array's last element ends at 0x104
+    // #[register(offset = 0xF4, access = "RW", count = 4)]
+    // long: u32,
+}
+
+#[register_block]
+pub struct Channel {
+    #[register(offset = 0x00, access = "RW")]
+    src: u32,
+    #[register(offset = 0x04, access = "RW")]
+    len: u32,
+}
+
+#[register_block(max_offset = 0x1F)]
+pub struct Dma {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    // the channel's registers end exactly at max_offset
+    #[subblock(offset = 0x18)]
+    chan: Channel,
+    // This should cause a compile error: the sub-block starts in range but ends at 0x24
+    // #[subblock(offset = 0x1C)]
+    // late: Channel,
+}
+
+fn main() {
+    assert_eq!(Timer::<usize>::COUNTER_OFFSET, 0xFC);
+    assert_eq!(Timer::<usize>::BLOCK_SIZE, 0x100);
+
+    let mut buffer = [0u32; 0x100 / 4];
+    buffer[0xFC / 4] = 42;
+    let timer = Timer::new(buffer.as_mut_ptr() as usize);
+    timer.compare(2).write(7);
+    assert_eq!(timer.counter().read(), 42);
+    assert_eq!(buffer[(0x10 + 2 * 4) / 4], 7);
+
+    assert_eq!(Dma::<usize>::BLOCK_SIZE, 0x20);
+}
@@ -0,0 +1,211 @@
+use register_block::mock::{self, MmioBackend, MockAccess, VecBackend};
+use register_block::{register_block, AccessError};
+
+#[register_block]
+pub struct Spi {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u32,
+    #[register(offset = 0x08, access = "WO")]
+    data: u8,
+}
+
+#[register_block]
+pub struct Wide {
+    #[register(offset = 0x00, access = "RW", width = 128)]
+    low_first: u128,
+    #[register(offset = 0x10, access = "RW", width = 128, word_order = "high_first")]
+    high_first: u128,
+}
+
+#[register_block]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW", reset = 0x0000_00FF)]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    count: u32,
+    #[register(offset = 0x08, access = "RO", count = 2, reset = 0xAAAA)]
+    capture: u16,
+    #[register(offset = 0x0C, access = "RW", reset = 0x1234, endian = "big")]
+    id: u16,
+}
+
+// the driver under test
+fn send(spi: &Spi<usize>, byte: u8) {
+    spi.ctrl().modify(|v| v | 1);
+    while spi.status().read() & 1 == 0 {}
+    spi.data().write(byte);
+}
+
+// a device model whose status register has gone away
+struct Unplugged(VecBackend);
+
+impl MmioBackend for Unplugged {
+    fn read(&mut self, address: usize, bytes: &mut [u8]) {
+        self.0.read(address, bytes)
+    }
+    fn write(&mut self, address: usize, bytes: &[u8]) {
+        self.0.write(address, bytes)
+    }
+    fn try_read(&mut self, address: usize, bytes: &mut [u8]) -> Result<(), AccessError> {
+        if address == 0x4000_0004 {
+            return Err(AccessError { address });
+        }
+        self.read(address, bytes);
+        Ok(())
+    }
+}
+
+#[register_block]
+pub struct Fifo {
+    // an array isn't a single access: each element is read and written on its own
+    #[register(offset = 0x00, access = "RW", allow_any_type)]
+    words: [u16; 3],
+}
+
+fn main() {
+    // nothing is mapped at this address, all accesses go to the backend
+    let base = 0x4000_0000;
+    let backend = VecBackend::new(base, 0x10);
+    mock::install(backend.clone());
+    backend.set(base + 0x04, &1u32.to_ne_bytes());
+
+    send(&Spi::new(base), 0xA5);
+    assert_eq!(
+        backend.log(),
+        [
+            MockAccess::Read {
+                address: base,
+                value: 0
+            },
+            MockAccess::Write {
+                address: base,
+                value: 1
+            },
+            MockAccess::Read {
+                address: base + 0x04,
+                value: 1
+            },
+            MockAccess::Write {
+                address: base + 0x08,
+                value: 0xA5
+            },
+        ]
+    );
+    assert_eq!(backend.memory()[0x08], 0xA5);
+
+    // e.g. "configured once, then polled the status three times"
+    backend.clear_log();
+    let spi = Spi::new(base);
+    spi.ctrl().write(1);
+    for _ in 0..3 {
+        let _ = spi.status().read();
+    }
+    // counted by offset from the backend's base, like the `*_OFFSET` constants
+    assert_eq!(backend.write_count(Spi::<usize>::CTRL_OFFSET), 1);
+    assert_eq!(backend.read_count(0x00), 0);
+    assert_eq!(backend.read_count(0x04), 3);
+    assert_eq!(backend.write_count(0x04), 0);
+    assert_eq!(
+        backend.log(),
+        [
+            MockAccess::Write {
+                address: base,
+                value: 1
+            },
+            MockAccess::Read {
+                address: base + 0x04,
+                value: 1
+            },
+            MockAccess::Read {
+                address: base + 0x04,
+                value: 1
+            },
+            MockAccess::Read {
+                address: base + 0x04,
+                value: 1
+            },
+        ]
+    );
+
+    // fallible accesses report the faults a backend injects
+    mock::install(Unplugged(VecBackend::new(base, 0x10)));
+    let spi = Spi::new(base);
+    assert_eq!(spi.ctrl().try_read(), Ok(0));
+    assert_eq!(spi.ctrl().try_write(3), Ok(()));
+    assert_eq!(
+        spi.status().try_read(),
+        Err(AccessError {
+            address: base + 0x04
+        })
+    );
+
+    // split 128-bit registers access their words one by one, in the declared order
+    let backend = VecBackend::new(base, 0x20);
+    mock::install(backend.clone());
+    let wide = Wide::new(base);
+    let value = 0x4444_4444_3333_3333_2222_2222_1111_1111;
+    wide.low_first().write(value);
+    wide.high_first().write(value);
+    let write = |offset: usize, value: u128| MockAccess::Write {
+        address: base + offset,
+        value,
+    };
+    assert_eq!(
+        backend.log(),
+        [
+            write(0x00, 0x1111_1111),
+            write(0x04, 0x2222_2222),
+            write(0x08, 0x3333_3333),
+            write(0x0C, 0x4444_4444),
+            write(0x1C, 0x4444_4444),
+            write(0x18, 0x3333_3333),
+            write(0x14, 0x2222_2222),
+            write(0x10, 0x1111_1111),
+        ]
+    );
+    backend.clear_log();
+    assert_eq!(wide.high_first().read(), value);
+    assert_eq!(
+        backend.log()[0],
+        MockAccess::Read {
+            address: base + 0x1C,
+            value: 0x4444_4444
+        }
+    );
+
+    // registers start out at their reset values, until they're written
+    let backend = VecBackend::with_resets(base, 0x10, Timer::<usize>::REGISTERS);
+    mock::install(backend.clone());
+    let timer = Timer::new(base);
+    assert_eq!(timer.ctrl().read(), 0xFF);
+    assert_eq!(timer.count().read(), 0);
+    assert_eq!(timer.capture(1).read(), 0xAAAA);
+    assert_eq!(timer.id().read(), 0x1234);
+    assert_eq!(backend.memory()[0x0C..0x0E], [0x12, 0x34]);
+    timer.ctrl().write(0x3);
+    assert_eq!(timer.ctrl().read(), 0x3);
+
+    let backend = VecBackend::new(base, 0x08);
+    mock::install(backend.clone());
+    let fifo = Fifo::new(base);
+    fifo.words().write([1, 2, 3]);
+    assert_eq!(fifo.words().read(), [1, 2, 3]);
+    assert_eq!(backend.log().len(), 6);
+    assert_eq!(
+        backend.log()[5],
+        MockAccess::Read {
+            address: base + 0x04,
+            value: 3
+        }
+    );
+
+    // without a backend accesses go to memory again
+    mock::uninstall();
+    let mut buffer = [0u32; 3];
+    let spi = Spi::new(buffer.as_mut_ptr() as usize);
+    spi.ctrl().write(7);
+    assert_eq!(buffer[0], 7);
+    assert_eq!(spi.ctrl().try_read(), Ok(7));
+}
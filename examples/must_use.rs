@@ -0,0 +1,38 @@
+#![deny(unused_must_use)]
+use register_block::register_block;
+
+#[register_block(flat, must_use)]
+pub struct Spi {
+    #[register(offset = 0x00, access = "RW")]
+    #[bits(name = "enable", range = 0..1)]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u32,
+}
+
+// without `must_use`, only RC reads are `#[must_use]`
+#[register_block(flat)]
+pub struct Irq {
+    #[register(offset = 0x00, access = "RO")]
+    raw: u32,
+    #[register(offset = 0x04, access = "RC")]
+    pending: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    let spi = Spi::new(buffer.as_mut_ptr() as usize);
+    spi.write_ctrl(1);
+    assert!(spi.enable());
+    assert_eq!(spi.read_status(), 0);
+    // This should cause a compile error: the value read is dropped
+    // spi.read_status();
+    // This should cause a compile error: bitfield getters are `#[must_use]` too
+    // spi.enable();
+
+    let irq = Irq::new(buffer.as_mut_ptr() as usize);
+    irq.read_raw();
+    let _ = irq.read_pending();
+    // This should cause a compile error: the pending bits are cleared and lost
+    // irq.read_pending();
+}
@@ -0,0 +1,36 @@
+use register_block::register_block;
+
+#[register_block(flat, newtypes)]
+pub struct Adc {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RW")]
+    threshold: u32,
+    #[register(offset = 0x08, access = "RO", count = 2)]
+    sample: u16,
+}
+
+fn main() {
+    let mut buffer = [0u32; 3];
+    let base = buffer.as_mut_ptr() as usize;
+    let adc = Adc::new(base);
+
+    adc.write_ctrl(CtrlValue(0x1));
+    adc.write_threshold(0x800.into());
+    adc.modify_ctrl(|ctrl| CtrlValue(*ctrl | 0x2));
+    assert_eq!(buffer[..2], [0x3, 0x800]);
+
+    let threshold = adc.read_threshold();
+    assert_eq!(threshold, ThresholdValue(0x800));
+    assert_eq!(u32::from(threshold), 0x800);
+    // Deref gives access to the raw value
+    assert!(*threshold > 0x7FF);
+
+    unsafe { (base as *mut u32).add(2).write(0x0002_0001) };
+    assert_eq!(adc.read_sample(1), SampleValue(0x2));
+
+    // This should cause a compile error: a threshold value isn't a control value
+    // adc.write_ctrl(threshold);
+    // This should cause a compile error: neither is a bare integer
+    // adc.write_ctrl(0x1);
+}
@@ -0,0 +1,35 @@
+//! The generated code only needs `core`; std is only linked for the test harness.
+#![no_std]
+extern crate std;
+
+use register_block::register_block;
+
+#[register_block(flat, no_panic, instances = 2, stride = 0x20)]
+pub struct Dma {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RW", count = 4, stride = 4)]
+    addr: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 0x10];
+    let base = buffer.as_mut_ptr() as usize;
+    let dma = Dma::new(base);
+
+    dma.write_addr(3, 0xA);
+    dma.instance(1).write_ctrl(1);
+    assert_eq!(dma.read_addr(3), 0xA);
+
+    // index checks are left out even in debug builds: index 7 is the next instance's `ctrl`
+    assert_eq!(dma.read_addr(7), 1);
+    assert!(std::panic::catch_unwind(|| dma.instance(2).base_address()).is_ok());
+
+    // a short dump buffer is left alone instead of panicking
+    let mut short = [0xFFu8; 4];
+    dma.dump(&mut short);
+    assert_eq!(short, [0xFF; 4]);
+    let mut full = [0u8; Dma::<usize>::BLOCK_SIZE];
+    dma.dump(&mut full);
+    assert_eq!(full[0x10], 0xA);
+}
@@ -0,0 +1,73 @@
+use register_block::{register_block, split_at, BaseAddress, ConstantAddress, OffsetAddress};
+
+#[register_block]
+pub struct Parent {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+}
+
+#[register_block]
+pub struct Channel {
+    #[register(offset = 0x00, access = "RW")]
+    config: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 0x30];
+    let base = buffer.as_mut_ptr() as usize;
+
+    let parent = Parent::new(base);
+    let chan0 = Channel::new(OffsetAddress::<_, 0x40>::new(base));
+    let chan1 = Channel::new(OffsetAddress::<_, 0x80>::new(base));
+
+    parent.ctrl().write(1);
+    chan0.config().write(0xA);
+    chan1.config().write(0xB);
+    let word = |offset: usize| unsafe { ((base + offset) as *const u32).read() };
+    assert_eq!(word(0x00), 1);
+    assert_eq!(word(0x40), 0xA);
+    assert_eq!(word(0x80), 0xB);
+
+    // the base can be recovered from a block, e.g. to place the next channel after it
+    assert_eq!(chan1.base_address(), base + 0x80);
+    let chan2 = Channel::new(OffsetAddress::<_, 0x40>::new(chan1.base()));
+    assert_eq!(chan2.base_address(), base + 0xC0);
+    assert_eq!(parent.base(), base);
+
+    // offsets compose, and work on top of constant bases too
+    let nested = OffsetAddress::<_, 0x4>::new(OffsetAddress::<_, 0x80>::new(base));
+    assert_eq!(Channel::new(nested).config().read(), word(0x84));
+    let constant = OffsetAddress::<_, 0x100>::new(ConstantAddress::<0x4000_0000>);
+    assert_eq!(constant.base_address(), 0x4000_0100);
+
+    // one region split between two drivers, the second block right behind the first
+    const _: () = assert!(Channel::<usize>::BLOCK_SIZE <= 0x8);
+    let (low, high) = split_at::<_, 0x8>(base + 0x90);
+    let first = Channel::new(low);
+    let second = Channel::new(high);
+    first.config().write(0x1);
+    second.config().write(0x2);
+    assert_eq!(word(0x90), 0x1);
+    assert_eq!(word(0x98), 0x2);
+    assert_eq!(second.base_address(), first.base_address() + 0x8);
+
+    // bases can be borrowed as well
+    let shared = ConstantAddress::<0x4000_0000>;
+    let borrowed = Channel::new(&shared);
+    assert_eq!(borrowed.ptr_status() as usize, 0x4000_0004);
+    let by_ref = Parent::new(&base);
+    assert_eq!(by_ref.ctrl().read(), 1);
+
+    // a base too close to the top of the address space is caught instead of wrapping around
+    let top = Channel::new(usize::MAX - 2);
+    assert_eq!(top.ptr_config() as usize, usize::MAX - 2);
+    #[cfg(debug_assertions)]
+    assert!(std::panic::catch_unwind(|| top.ptr_status()).is_err());
+    #[cfg(debug_assertions)]
+    assert!(
+        std::panic::catch_unwind(|| OffsetAddress::<_, 0x10>::new(top.base()).base_address())
+            .is_err()
+    );
+}
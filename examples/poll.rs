@@ -0,0 +1,77 @@
+use register_block::mock::{self, MmioBackend, VecBackend};
+use register_block::{register_block, TimeoutError, RW};
+
+#[register_block(flat)]
+pub struct Pll {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u32,
+}
+
+const LOCKED: u32 = 1 << 0;
+const BUSY: u32 = 1 << 4;
+
+// a device model that locks after a few reads of `status`
+struct Locking {
+    memory: VecBackend,
+    reads: usize,
+    lock_after: usize,
+}
+
+impl MmioBackend for Locking {
+    fn read(&mut self, address: usize, bytes: &mut [u8]) {
+        if address == 0x4000_0004 {
+            self.reads += 1;
+            let status = if self.reads > self.lock_after {
+                LOCKED
+            } else {
+                BUSY
+            };
+            self.memory.set(address, &status.to_ne_bytes());
+        }
+        self.memory.read(address, bytes)
+    }
+    fn write(&mut self, address: usize, bytes: &[u8]) {
+        self.memory.write(address, bytes)
+    }
+}
+
+fn locking(lock_after: usize) -> Locking {
+    Locking {
+        memory: VecBackend::new(0x4000_0000, 0x08),
+        reads: 0,
+        lock_after,
+    }
+}
+
+fn main() {
+    let pll = Pll::new(0x4000_0000);
+
+    // the poll keeps reading until the device reports the lock
+    mock::install(locking(5));
+    pll.poll_status_set(LOCKED);
+    assert_eq!(pll.read_status(), LOCKED);
+    mock::uninstall();
+
+    mock::install(locking(5));
+    pll.poll_status_clear(BUSY);
+    mock::uninstall();
+
+    // a budget of 5 reads isn't enough, 6 are
+    mock::install(locking(5));
+    assert_eq!(pll.poll_status_set_timeout(LOCKED, 5), Err(TimeoutError));
+    mock::uninstall();
+    mock::install(locking(5));
+    assert_eq!(pll.poll_status_set_timeout(LOCKED, 6), Ok(()));
+    mock::uninstall();
+
+    // the wrappers poll too; bits that are already in place return right away
+    let mut buffer = [0u32; 1];
+    let reg = unsafe { RW::<u32>::new(buffer.as_mut_ptr() as usize) };
+    reg.write(0b1010);
+    reg.poll_set(0b1000);
+    reg.poll_clear(0b0101);
+    assert_eq!(reg.poll_set_timeout(0b0001, 3), Err(TimeoutError));
+    assert_eq!(reg.poll_clear_timeout(0b0010, 0), Err(TimeoutError));
+}
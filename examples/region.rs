@@ -0,0 +1,49 @@
+use register_block::{register_block, RW};
+
+/// Stand-in for a memory mapping that is released when dropped.
+struct Mapping {
+    memory: *mut [u32],
+}
+
+impl Mapping {
+    fn new(words: usize) -> Self {
+        Mapping {
+            memory: Box::into_raw(vec![0u32; words].into_boxed_slice()),
+        }
+    }
+    fn address(&self) -> usize {
+        self.memory as *mut u32 as usize
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.memory) });
+    }
+}
+
+#[register_block]
+pub struct Regs {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+}
+
+fn main() {
+    let mapping = Mapping::new(4);
+
+    // a wrapper bound to the mapping can't outlive it
+    let data = unsafe { RW::<u32>::with_region(mapping.address() + 4, &mapping) };
+    data.write(5);
+    assert_eq!(data.read(), 5);
+    // This should cause a compile error: `data` still borrows `mapping`
+    // drop(mapping);
+    assert_eq!(data.read(), 5);
+
+    // wrappers returned by generated accessors borrow the block
+    let regs = Regs::new(mapping.address());
+    let ctrl = regs.ctrl();
+    ctrl.write(1);
+    // This should cause a compile error: `ctrl` still borrows `regs`
+    // drop(regs);
+    assert_eq!(ctrl.read(), 1);
+}
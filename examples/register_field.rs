@@ -0,0 +1,44 @@
+use register_block::{Access, AccessDenied, RegisterField};
+
+// register descriptions as a scripting layer might load them from a config file
+const LAYOUT: [(&str, usize, Access); 4] = [
+    ("ctrl", 0x00, Access::RW),
+    ("status", 0x04, Access::RO),
+    ("irq_clear", 0x08, Access::WC),
+    ("tx", 0x0C, Access::WO),
+];
+
+fn main() {
+    let mut buffer = [0u32, 0x11, 0xFF, 0];
+    let base = buffer.as_mut_ptr() as usize;
+    let fields: Vec<RegisterField<u32>> = LAYOUT
+        .iter()
+        .map(|&(_, offset, access)| unsafe { RegisterField::at(base, offset, access) })
+        .collect();
+    let [ctrl, status, irq_clear, tx] = fields[..] else {
+        unreachable!()
+    };
+
+    assert_eq!(ctrl.address(), base);
+    assert_eq!(status.access(), Access::RO);
+    ctrl.write(0x5).unwrap();
+    assert_eq!(ctrl.read(), Ok(0x5));
+    assert_eq!(status.read(), Ok(0x11));
+    tx.write(0xA5).unwrap();
+    irq_clear.clear().unwrap();
+    assert_eq!(buffer, [0x5, 0x11, 0, 0xA5]);
+
+    // operations the access type doesn't allow fail without touching the bus
+    let denied = |field: RegisterField<u32>| AccessDenied {
+        address: field.address(),
+        access: field.access(),
+    };
+    assert_eq!(status.write(1), Err(denied(status)));
+    assert_eq!(tx.read(), Err(denied(tx)));
+    assert_eq!(ctrl.clear(), Err(denied(ctrl)));
+    assert_eq!(
+        status.write(1).unwrap_err().to_string(),
+        format!("RO register at {:#x} doesn't allow this access", base + 4)
+    );
+    assert_eq!(buffer, [0x5, 0x11, 0, 0xA5]);
+}
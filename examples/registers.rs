@@ -0,0 +1,80 @@
+use register_block::{register_block, Access, RegisterInfo};
+
+/// The analog-to-digital converter, with four channels.
+#[register_block]
+pub struct Adc {
+    /// Control register.
+    ///
+    /// Only the first line of the docs makes it into the memory map.
+    #[register(offset = 0x00, access = "RW", reset = 0x10)]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RC")]
+    status: u16,
+    /// Conversion results, one per channel | oldest first
+    #[register(offset = 0x10, access = "RO", count = 4, stride = 4)]
+    result: u16,
+}
+
+fn main() {
+    assert_eq!(
+        Adc::<usize>::REGISTERS,
+        [
+            RegisterInfo {
+                name: "ctrl",
+                offset: 0x00,
+                width: 32,
+                access: Access::RW,
+                count: 1,
+                stride: 4,
+                reset: Some(0x10),
+            },
+            RegisterInfo {
+                name: "status",
+                offset: 0x04,
+                width: 16,
+                access: Access::RC,
+                count: 1,
+                stride: 2,
+                reset: None,
+            },
+            RegisterInfo {
+                name: "result",
+                offset: 0x10,
+                width: 16,
+                access: Access::RO,
+                count: 4,
+                stride: 4,
+                reset: None,
+            },
+        ]
+    );
+
+    // e.g. a generic register explorer
+    let readable: Vec<_> = Adc::<usize>::REGISTERS
+        .iter()
+        .filter(|info| matches!(info.access, Access::RW | Access::RO))
+        .map(|info| info.name)
+        .collect();
+    assert_eq!(readable, ["ctrl", "result"]);
+
+    // offsets by name, e.g. for register names read from a config file
+    assert_eq!(Adc::<usize>::offset_of("status"), 0x04);
+    assert_eq!(Adc::<usize>::try_offset_of("result"), Some(0x10));
+    assert_eq!(Adc::<usize>::try_offset_of("missing"), None);
+    const CTRL_OFFSET: usize = Adc::<usize>::offset_of("ctrl");
+    assert_eq!(CTRL_OFFSET, 0x00);
+    assert!(std::panic::catch_unwind(|| Adc::<usize>::offset_of("missing")).is_err());
+    // This should cause a compile error: there's no such register
+    // const TYPO: usize = Adc::<usize>::offset_of("stauts");
+
+    // the memory map of the docs, e.g. for a `--dump-map` command
+    let map = Adc::<usize>::MEMORY_MAP;
+    assert!(map.starts_with("| Offset | Register | Access | Type | Description |\n"));
+    assert!(map
+        .lines()
+        .any(|row| row == "| 0x00 | `ctrl` | RW | `u32` | Control register. |"));
+    // pipes in the docs are escaped, so they don't split the cell
+    assert!(map.lines().any(|row| row
+        == "| 0x10 + 0x4 × 0..4 | `result` | RO | `u16` | Conversion results, one per channel \\| oldest first |"));
+    assert_eq!(map.lines().count(), 2 + 3);
+}
@@ -0,0 +1,28 @@
+use register_block::register_block;
+
+#[register_block]
+#[reserved(offset = 0x40, len = 0xC0)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[reserved(offset = 0x04, len = 0x0C)]
+    _gap: (),
+    #[register(offset = 0x10, access = "RO")]
+    count: u32,
+    // This should cause a compile error: `late` lies inside the reserved range 0x40..0x100
+    // #[register(offset = 0x80, access = "RW")]
+    // late: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 0x100 / 4];
+    buffer[0x10 / 4] = 42;
+    let timer = Timer::new(buffer.as_mut_ptr() as usize);
+
+    timer.ctrl().write(1);
+    assert_eq!(timer.count().read(), 42);
+    assert_eq!(buffer[0], 1);
+
+    // reserved ranges count towards the size of the block
+    assert_eq!(Timer::<usize>::BLOCK_SIZE, 0x100);
+}
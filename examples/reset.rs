@@ -0,0 +1,39 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW", reset = 0x0000_00FF)]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO", reset = 0x1)]
+    status: u32,
+    #[register(offset = 0x08, access = "WO", reset = 0)]
+    load: u32,
+    #[register(offset = 0x0C, access = "RW")]
+    scratch: u32,
+    #[register(offset = 0x10, access = "RW", count = 2, reset = 0xAAAA)]
+    compare: u16,
+}
+
+fn main() {
+    let mut buffer = [0xFFFF_FFFFu32; 6];
+    let base = buffer.as_mut_ptr() as usize;
+    let timer = Timer::new(base);
+
+    assert_eq!(Timer::<usize>::RESET_CTRL, 0xFF);
+    assert_eq!(Timer::<usize>::RESET_STATUS, 0x1);
+
+    timer.reset_ctrl();
+    assert_eq!(timer.ctrl().read(), 0xFF);
+
+    timer.ctrl().write(0x1234);
+    timer.reset_all();
+    let word = |index: usize| unsafe { (base as *const u32).add(index).read() };
+    assert_eq!(word(0), 0xFF);
+    // read-only registers are left alone
+    assert_eq!(word(1), 0xFFFF_FFFF);
+    assert_eq!(word(2), 0);
+    // registers without a reset value are left alone
+    assert_eq!(word(3), 0xFFFF_FFFF);
+    assert_eq!(word(4), 0xAAAA_AAAA);
+    assert_eq!(word(5), 0xFFFF_FFFF);
+}
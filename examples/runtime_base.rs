@@ -0,0 +1,41 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use register_block::register_block;
+
+#[register_block]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RW")]
+    data: u32,
+}
+
+// The base isn't known until the device tree has been parsed
+static UART_BASE: AtomicUsize = AtomicUsize::new(0);
+static UART: Uart<&AtomicUsize> = Uart::new(&UART_BASE);
+
+// This should cause a compile error: a constant base can't be moved
+// fn rebase(uart: Uart<register_block::ConstantAddress<0x1000>>) {
+//     uart.with_base(register_block::ConstantAddress::<0x2000>);
+// }
+
+fn main() {
+    let mut first = [0u32; 1];
+    let mut second = [0u32; 1];
+
+    UART_BASE.store(first.as_mut_ptr() as usize, Ordering::Relaxed);
+    UART.data().write(1);
+    UART_BASE.store(second.as_mut_ptr() as usize, Ordering::Relaxed);
+    UART.data().write(2);
+
+    assert_eq!(first[0], 1);
+    assert_eq!(second[0], 2);
+
+    // probing starts out at a placeholder base
+    let probe = Uart::new(0usize);
+    let mut uart = probe.with_base(first.as_mut_ptr() as usize);
+    uart.data().write(3);
+    assert_eq!(first[0], 3);
+    uart.set_base(second.as_mut_ptr() as usize);
+    assert_eq!(uart.base(), second.as_mut_ptr() as usize);
+    uart.data().write(4);
+    assert_eq!(second[0], 4);
+    assert_eq!(first[0], 3);
+}
@@ -0,0 +1,19 @@
+use register_block::{register_block, RW};
+
+#[register_block]
+pub struct Regs {
+    #[register(offset = 0x00, access = "RW")]
+    data: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 1];
+    let regs = Regs::new(buffer.as_mut_ptr() as usize);
+
+    // with `send-sync` a register handle can be moved to another thread
+    let data: RW<'_, u32> = regs.data();
+    std::thread::scope(|scope| {
+        scope.spawn(move || data.write(7));
+    });
+    assert_eq!(regs.data().read(), 7);
+}
@@ -0,0 +1,50 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Gpio {
+    #[register(offset = 0x00, access = "WO", shadow = true)]
+    out: u32,
+    #[register(offset = 0x04, access = "WO", shadow = true, reset = 0xF0)]
+    dir: u32,
+    #[register(offset = 0x08, access = "RO")]
+    input: u32,
+    // This should cause a compile error: RW registers can be read back, they need no shadow
+    // #[register(offset = 0x0C, access = "RW", shadow = true)]
+    // pull: u32,
+}
+
+#[register_block(flat)]
+pub struct Pwm {
+    #[register(offset = 0x00, access = "WO", shadow = true)]
+    duty: u16,
+    #[register(offset = 0x02, access = "WO")]
+    period: u16,
+}
+
+fn main() {
+    let mut buffer = [0u32; 3];
+    let gpio = Gpio::new(buffer.as_mut_ptr() as usize);
+
+    // the shadow starts at the reset value
+    assert_eq!(gpio.shadow_out(), 0);
+    assert_eq!(gpio.shadow_dir(), 0xF0);
+
+    gpio.write_out(0b0101);
+    gpio.modify_out(|out| out | 0b1000);
+    assert_eq!(gpio.shadow_out(), 0b1101);
+    gpio.modify_dir(|dir| dir & !0x10);
+    assert_eq!(buffer[0], 0b1101);
+    assert_eq!(buffer[1], 0xE0);
+
+    // writes through the accessor bypass the shadow
+    gpio.out().write(0);
+    assert_eq!(gpio.shadow_out(), 0b1101);
+
+    let mut buffer = [0u16; 2];
+    let pwm = Pwm::new(buffer.as_mut_ptr() as usize);
+    pwm.write_duty(100);
+    pwm.modify_duty(|duty| duty * 2);
+    pwm.write_period(1000);
+    assert_eq!(pwm.shadow_duty(), 200);
+    assert_eq!(buffer, [200, 1000]);
+}
@@ -0,0 +1,42 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Sensor {
+    /// Calibration offset, in 1/16 degrees
+    #[register(offset = 0x00, access = "RW")]
+    calibration: i32,
+    #[register(offset = 0x04, access = "RW")]
+    #[bits(name = "delta", range = 0..8)]
+    #[bits(name = "negative", range = 31..32)]
+    #[bits(name = "middle", range = 16..24)]
+    sample: i32,
+    #[register(offset = 0x08, access = "WC")]
+    error: i16,
+}
+
+fn main() {
+    let mut buffer = [0u32; 3];
+    let base = buffer.as_mut_ptr() as usize;
+    let sensor = Sensor::new(base);
+
+    sensor.calibration().write(-40);
+    assert_eq!(sensor.calibration().read(), -40);
+    sensor.calibration().modify(|v| v * 2);
+    assert_eq!(sensor.calibration().read(), -80);
+    assert_eq!(buffer[0], (-80i32) as u32);
+
+    // bitfields see the raw two's complement bit pattern, without sign extension
+    sensor.sample().write(-2);
+    assert_eq!(sensor.delta(), 0xFE);
+    assert!(sensor.negative());
+    assert_eq!(sensor.middle(), 0xFF);
+    sensor.set_middle(0x12);
+    sensor.set_negative(false);
+    assert_eq!(sensor.sample().read(), 0x7F12_FFFE);
+    assert!(!sensor.negative());
+    // sign-extend by hand where a bitfield holds a signed quantity
+    assert_eq!(sensor.delta() as i8, -2);
+
+    sensor.error().clear();
+    assert_eq!(buffer[2], 0);
+}
@@ -0,0 +1,48 @@
+use register_block::register_block;
+
+#[register_block(snapshot)]
+pub struct Adc {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    /// conversion results
+    #[register(offset = 0x04, access = "RO", count = 3)]
+    data: u16,
+    #[register(offset = 0x0C, access = "W1C")]
+    flags: u32,
+    // write-only and clear registers have no place in a snapshot
+    #[register(offset = 0x10, access = "WO")]
+    start: u32,
+    // reading this would clear it
+    #[register(offset = 0x14, access = "RC")]
+    overrun: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 6];
+    let base = buffer.as_mut_ptr() as usize;
+    buffer[0] = 0x11;
+    buffer[1] = u32::from_ne_bytes([0x01, 0x00, 0x02, 0x00]);
+    buffer[2] = u32::from_ne_bytes([0x03, 0x00, 0x00, 0x00]);
+    buffer[3] = 0b101;
+    buffer[5] = 0xEE;
+    let adc = Adc::new(base);
+
+    let before = adc.snapshot();
+    assert_eq!(
+        before,
+        AdcSnapshot {
+            ctrl: 0x11,
+            data: [1, 2, 3],
+            flags: 0b101,
+        }
+    );
+    // the RC register wasn't touched
+    assert_eq!(buffer[5], 0xEE);
+
+    // snapshots are plain values, so they can be kept around and compared
+    adc.ctrl().write(0x12);
+    let after = adc.snapshot();
+    assert_ne!(before, after);
+    assert_eq!(after.ctrl, 0x12);
+    assert_eq!(after.data, before.data);
+}
@@ -0,0 +1,93 @@
+use register_block::mock::{self, MockAccess, VecBackend};
+use register_block::register_block;
+
+#[register_block]
+#[snapshot(group = "time", fields = [subsec, sec])]
+#[snapshot(group = "date", fields = [year, day])]
+pub struct Rtc {
+    #[register(offset = 0x00, access = "RO")]
+    subsec: u32,
+    #[register(offset = 0x04, access = "RO")]
+    sec: u32,
+    #[register(offset = 0x08, access = "RW")]
+    day: u16,
+    #[register(offset = 0x0C, access = "RO")]
+    year: u16,
+    #[register(offset = 0x10, access = "WO")]
+    alarm: u32,
+    // This should cause a compile error: adding `alarm` to the fields of a #[snapshot], WO can't be read
+    // This should cause a compile error: listing `sec` twice in the fields of a #[snapshot]
+}
+
+fn main() {
+    let base = 0x4000_0000;
+    let backend = VecBackend::new(base, 0x10);
+    mock::install(backend.clone());
+    backend.set(base, &0x1234_5678u32.to_ne_bytes());
+    backend.set(base + 0x04, &42u32.to_ne_bytes());
+    backend.set(base + 0x08, &250u16.to_ne_bytes());
+    backend.set(base + 0x0C, &2026u16.to_ne_bytes());
+
+    // adjacent registers filling an aligned range are read in one go on 64-bit targets
+    let rtc = Rtc::new(base);
+    assert_eq!(rtc.read_time(), (0x1234_5678, 42));
+    let expected = if cfg!(target_pointer_width = "64") {
+        vec![MockAccess::Read {
+            address: base,
+            value: u64::from_ne_bytes(backend.memory()[..8].try_into().unwrap()) as u128,
+        }]
+    } else {
+        vec![
+            MockAccess::Read {
+                address: base,
+                value: 0x1234_5678,
+            },
+            MockAccess::Read {
+                address: base + 0x04,
+                value: 42,
+            },
+        ]
+    };
+    assert_eq!(backend.log(), expected);
+
+    // the others are read one by one, in the order listed
+    backend.clear_log();
+    assert_eq!(rtc.read_date(), (2026, 250));
+    assert_eq!(
+        backend.log(),
+        [
+            MockAccess::Read {
+                address: base + 0x0C,
+                value: 2026,
+            },
+            MockAccess::Read {
+                address: base + 0x08,
+                value: 250,
+            },
+        ]
+    );
+    mock::uninstall();
+
+    // a base that isn't aligned to the wider access falls back to the listed order too
+    let base = 0x4000_1004;
+    let backend = VecBackend::new(base, 0x10);
+    mock::install(backend.clone());
+    backend.set(base, &7u32.to_ne_bytes());
+    backend.set(base + 0x04, &8u32.to_ne_bytes());
+    let rtc = Rtc::new(base);
+    assert_eq!(rtc.read_time(), (7, 8));
+    assert_eq!(
+        backend.log(),
+        [
+            MockAccess::Read {
+                address: base,
+                value: 7,
+            },
+            MockAccess::Read {
+                address: base + 0x04,
+                value: 8,
+            },
+        ]
+    );
+    mock::uninstall();
+}
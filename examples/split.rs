@@ -0,0 +1,43 @@
+use register_block::register_block;
+
+#[register_block(flat)]
+pub struct Accel {
+    // four 32-bit accesses, bits 0..32 first
+    #[register(offset = 0x00, access = "RW", width = 128)]
+    key: u128,
+    // four 32-bit accesses, bits 96..128 first
+    #[register(offset = 0x10, access = "RW", width = 128, word_order = "high_first")]
+    iv: u128,
+    #[register(offset = 0x20, access = "RO", width = 128, word_order = "low_first")]
+    digest: u128,
+    // This should cause a compile error: `word_order` without width = 128
+    // #[register(offset = 0x30, access = "RW", word_order = "high_first")]
+    // plain: u128,
+    // This should cause a compile error: only u128 registers can be split
+    // #[register(offset = 0x30, access = "RW", width = 128)]
+    // signed: i128,
+}
+
+fn main() {
+    // the words only need to be 4-byte aligned, so start the block off a 16-byte boundary
+    let mut buffer = [0u32; 13];
+    let start = buffer.as_mut_ptr() as usize;
+    let base = if start % 16 == 0 { start + 4 } else { start };
+    let accel = Accel::new(base);
+
+    // whatever the order, the words are laid out like a native u128
+    let value = 0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210u128;
+    accel.write_key(value);
+    accel.write_iv(value);
+    let words = |index: usize| unsafe { ((base + index * 16) as *const u128).read_unaligned() };
+    assert_eq!(words(0), value);
+    assert_eq!(words(1), value);
+    assert_eq!(accel.read_key(), value);
+    assert_eq!(accel.read_iv(), value);
+
+    unsafe { ((base + 0x20) as *mut u128).write_unaligned(!value) };
+    assert_eq!(accel.read_digest(), !value);
+
+    accel.write_iv_masked(0, u128::MAX << 64);
+    assert_eq!(accel.read_iv(), value & u64::MAX as u128);
+}
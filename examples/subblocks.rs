@@ -0,0 +1,38 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct ChannelRegs {
+    #[register(offset = 0x00, access = "RW")]
+    config: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u32,
+}
+
+#[register_block]
+pub struct Dma {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    /// First DMA channel
+    #[subblock(offset = 0x100, ty = ChannelRegs)]
+    chan0: (),
+    // `ty` defaults to the field type
+    #[subblock(offset = 0x200)]
+    chan1: ChannelRegs,
+}
+
+fn main() {
+    let mut buffer = [0u32; 0x210 / 4];
+    let base = buffer.as_mut_ptr() as usize;
+    let dma = Dma::new(base);
+
+    dma.ctrl().write(1);
+    dma.chan0().config().write(0xA0);
+    dma.chan1().config().write(0xB0);
+    let word = |offset: usize| unsafe { ((base + offset) as *const u32).read() };
+    assert_eq!(word(0x000), 1);
+    assert_eq!(word(0x100), 0xA0);
+    assert_eq!(word(0x200), 0xB0);
+
+    assert_eq!(Dma::<usize>::CHAN1_OFFSET, 0x200);
+    assert_eq!(Dma::<usize>::BLOCK_SIZE, 0x208);
+}
@@ -0,0 +1,36 @@
+use register_block::{register_block, UnalignedRW};
+
+#[register_block]
+pub struct Packed {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u8,
+    // a 32-bit register straight after a byte-wide one
+    #[register(offset = 0x01, access = "RW", unchecked)]
+    data: u32,
+    // This should cause a compile error: `unchecked` registers can't have bitfields
+    // #[register(offset = 0x05, access = "RO", unchecked)]
+    // #[bits(name = "ready", range = 0..1)]
+    // status: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    let base = buffer.as_mut_ptr() as usize;
+    let regs = Packed::new(base);
+
+    // no alignment assertion fires, even in debug builds
+    unsafe { regs.data().write_unchecked(0x1234_5678) };
+    assert_eq!(unsafe { regs.data().read_unchecked() }, 0x1234_5678);
+    // This should cause a compile error: the register has no safe accesses
+    // regs.data().read();
+    assert_eq!(
+        unsafe { ((base + 1) as *const u32).read_unaligned() },
+        0x1234_5678
+    );
+    regs.ctrl().write(0xAB);
+    assert_eq!(unsafe { regs.data().read_unchecked() }, 0x1234_5678);
+
+    let raw = unsafe { UnalignedRW::<u16>::new(base + 3) };
+    unsafe { raw.write_unchecked(0xBEEF) };
+    assert_eq!(unsafe { raw.read_unchecked() }, 0xBEEF);
+}
@@ -0,0 +1,52 @@
+use register_block::mock::{self, MmioBackend, VecBackend};
+use register_block::{register_block, WriteVerifyError};
+
+#[register_block(flat)]
+pub struct Gpio {
+    #[register(offset = 0x00, access = "RW")]
+    dir: u32,
+    #[register(offset = 0x04, access = "RW")]
+    out: u32,
+}
+
+// a device model whose `out` register only implements its low byte
+struct Masked(VecBackend);
+
+impl MmioBackend for Masked {
+    fn read(&mut self, address: usize, bytes: &mut [u8]) {
+        self.0.read(address, bytes)
+    }
+    fn write(&mut self, address: usize, bytes: &[u8]) {
+        let mut bytes = bytes.to_vec();
+        if address == 0x4000_0004 {
+            bytes[1..].fill(0);
+        }
+        self.0.write(address, &bytes)
+    }
+}
+
+fn main() {
+    // plain memory holds whatever is written
+    let mut buffer = [0u32; 2];
+    let gpio = Gpio::new(buffer.as_mut_ptr() as usize);
+    assert!(gpio.write_dir_verify(0xDEAD_BEEF));
+    assert_eq!(gpio.try_write_out_verify(0x1234), Ok(()));
+    assert_eq!(buffer, [0xDEAD_BEEF, 0x1234]);
+
+    // bits the register doesn't implement don't read back
+    let base = 0x4000_0000;
+    mock::install(Masked(VecBackend::new(base, 0x08)));
+    let gpio = Gpio::new(base);
+    assert!(gpio.write_dir_verify(0xFFFF_FFFF));
+    assert!(gpio.write_out_verify(0xA5));
+    assert!(!gpio.write_out_verify(0x1A5));
+    assert_eq!(
+        gpio.try_write_out_verify(0x1A5),
+        Err(WriteVerifyError::Mismatch {
+            address: base + 0x04,
+            written: 0x1A5,
+            read: 0xA5,
+        })
+    );
+    mock::uninstall();
+}
@@ -0,0 +1,37 @@
+use register_block::register_block;
+
+pub type Word = u32;
+
+// the layout as signed off in the design review
+#[register_block(verify = [
+    (0x00, 4),
+    (0x04, 2),
+    (0x06, 1),
+    (0x08, 4),
+    (0x10, 4, count = 4),
+    (0x20, 2, count = 2, stride = 4),
+])]
+pub struct Spi {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u16,
+    #[register(offset = 0x06, access = "RO")]
+    level: u8,
+    #[register(offset = 0x08, access = "WO", width = 32)]
+    data: Word,
+    #[register(offset = 0x10, access = "RW", count = 4)]
+    cs: u32,
+    #[register(offset = 0x20, access = "RO", count = 2, stride = 4)]
+    fifo_level: u16,
+    // This should cause a compile error: moving `level` to offset 0x07, it no longer matches its row
+    // This should cause a compile error: changing `status` to a u32, it no longer matches its row
+    // This should cause a compile error: dropping the last row, the table no longer covers every register
+    // This should cause a compile error: changing `cs` to `count = 3`, it no longer matches its row
+}
+
+fn main() {
+    // nothing to check at runtime, the table is verified while compiling
+    assert_eq!(Spi::<usize>::REGISTERS.len(), 6);
+    assert_eq!(Spi::<usize>::LEVEL_OFFSET, 0x06);
+}
@@ -0,0 +1,42 @@
+use register_block::{register_block, RegisterBlockView};
+
+#[register_block]
+pub struct Uart {
+    #[register(offset = 0x00, access = "RW")]
+    data: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u32,
+}
+
+#[register_block]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    load: u32,
+    #[register(offset = 0x10, access = "RO")]
+    count: u64,
+}
+
+// generic code can check a block against the mapping it was given
+fn fits_in<B: RegisterBlockView>(block: &B, mapping: &[u32]) -> bool {
+    let start = mapping.as_ptr() as usize;
+    let end = start + core::mem::size_of_val(mapping);
+    block.base_address() >= start && block.base_address() + block.block_size() <= end
+}
+
+fn main() {
+    let mut buffer = [0u32; 8];
+    let base = buffer.as_mut_ptr() as usize;
+    let uart = Uart::new(base);
+    let timer = Timer::new(base);
+    let late_timer = Timer::new(base + 0x10);
+
+    assert!(fits_in(&uart, &buffer));
+    assert!(fits_in(&timer, &buffer));
+    assert!(!fits_in(&late_timer, &buffer));
+
+    // the trait is dyn compatible, so different blocks can share a list
+    let blocks: [&dyn RegisterBlockView; 3] = [&uart, &timer, &late_timer];
+    let sizes: Vec<usize> = blocks.iter().map(|block| block.block_size()).collect();
+    assert_eq!(sizes, [0x08, 0x18, 0x18]);
+    assert_eq!(blocks[2].base_address(), base + 0x10);
+}
@@ -0,0 +1,66 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Mailbox {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO")]
+    status: u32,
+    /// Message buffer shared with the other core.
+    #[window(offset = 0x10, len = 0x20)]
+    sram: (),
+    #[register(access = "WO")]
+    doorbell: u32,
+    // This should cause a compile error: a register at offset 0x20, it lies inside the window
+}
+
+#[register_block(no_panic)]
+pub struct Lenient {
+    #[window(offset = 0x00, len = 0x08)]
+    data: (),
+}
+
+fn main() {
+    let mut buffer = [0u32; 13];
+    let base = buffer.as_mut_ptr() as usize;
+    let mailbox = Mailbox::new(base);
+    assert_eq!(Mailbox::<usize>::SRAM_OFFSET, 0x10);
+    assert_eq!(Mailbox::<usize>::SRAM_LEN, 0x20);
+    assert_eq!(Mailbox::<usize>::DOORBELL_OFFSET, 0x30);
+    assert_eq!(Mailbox::<usize>::BLOCK_SIZE, 0x34);
+
+    // writes land at their offset in the window, reads see the backing memory
+    mailbox.sram_write(2, b"hello");
+    mailbox.sram_write(0x1C, &[0xAA; 4]);
+    mailbox.ctrl().write(1);
+    let bytes = unsafe { core::slice::from_raw_parts(base as *const u8, 0x34) };
+    assert_eq!(&bytes[0x12..0x17], b"hello");
+    assert_eq!(&bytes[0x2C..0x30], &[0xAA; 4]);
+    assert_eq!(bytes[..4], 1u32.to_ne_bytes());
+
+    unsafe { ((base + 0x10) as *mut [u8; 4]).write(*b"MBOX") };
+    let mut out = [0; 7];
+    mailbox.sram_read(0, &mut out);
+    assert_eq!(&out, b"MBOXllo");
+    let mut tail = [0; 4];
+    mailbox.sram_read(0x1C, &mut tail);
+    assert_eq!(tail, [0xAA; 4]);
+    // an empty range at the end still fits
+    mailbox.sram_read(0x20, &mut []);
+
+    // ranges past the end of the window are caught
+    assert!(std::panic::catch_unwind(|| mailbox.sram_read(0x1E, &mut [0; 4])).is_err());
+    assert!(std::panic::catch_unwind(|| mailbox.sram_write(0x21, &[])).is_err());
+    assert!(std::panic::catch_unwind(|| mailbox.sram_write(usize::MAX, &[1])).is_err());
+
+    // `no_panic` blocks skip them instead
+    let mut data = [0u8; 8];
+    let lenient = Lenient::new(data.as_mut_ptr() as usize);
+    lenient.data_write(4, &[1, 2, 3, 4, 5]);
+    assert_eq!(data, [0; 8]);
+    let lenient = Lenient::new(data.as_mut_ptr() as usize);
+    lenient.data_write(4, &[1, 2, 3, 4]);
+    let mut out = [0; 8];
+    lenient.data_read(0, &mut out);
+    assert_eq!(out, [0, 0, 0, 0, 1, 2, 3, 4]);
+}
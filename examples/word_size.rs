@@ -0,0 +1,68 @@
+use register_block::register_block;
+
+// registers numbered 0, 1, 2, ... in the datasheet, four bytes apart
+#[register_block(word_size = 4)]
+pub struct Indexed {
+    #[register(offset = 0, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 1, access = "RO")]
+    status: u32,
+    #[register(offset = 4, access = "RW", count = 3, stride = 2)]
+    channel: u32,
+    #[register(access = "WO")]
+    doorbell: u32,
+    // This should cause a compile error: `#[reserved(offset = 0x14, len = 4)]` here, its offset is in bytes
+}
+
+// the same map with byte offsets, in a module of its own for its `ChannelArray`
+mod bytes {
+    use register_block::register_block;
+
+    #[register_block]
+    pub struct Bytes {
+        #[register(offset = 0x00, access = "RW")]
+        ctrl: u32,
+        #[register(offset = 0x04, access = "RO")]
+        status: u32,
+        #[register(offset = 0x10, access = "RW", count = 3, stride = 8)]
+        channel: u32,
+        #[register(access = "WO")]
+        doorbell: u32,
+    }
+}
+
+use bytes::Bytes;
+
+fn main() {
+    assert_eq!(Indexed::<usize>::CTRL_OFFSET, Bytes::<usize>::CTRL_OFFSET);
+    assert_eq!(
+        Indexed::<usize>::STATUS_OFFSET,
+        Bytes::<usize>::STATUS_OFFSET
+    );
+    assert_eq!(
+        Indexed::<usize>::CHANNEL_OFFSET,
+        Bytes::<usize>::CHANNEL_OFFSET
+    );
+    assert_eq!(
+        Indexed::<usize>::DOORBELL_OFFSET,
+        Bytes::<usize>::DOORBELL_OFFSET
+    );
+    assert_eq!(Indexed::<usize>::BLOCK_SIZE, Bytes::<usize>::BLOCK_SIZE);
+    assert_eq!(Indexed::<usize>::MEMORY_MAP, Bytes::<usize>::MEMORY_MAP);
+
+    let mut buffer = [0u32; 11];
+    let base = buffer.as_mut_ptr() as usize;
+    let indexed = Indexed::new(base);
+    let bytes = Bytes::new(base);
+    assert_eq!(indexed.ptr_ctrl(), bytes.ptr_ctrl());
+    assert_eq!(indexed.ptr_status(), bytes.ptr_status());
+    for i in 0..3 {
+        assert_eq!(indexed.ptr_channel(i), bytes.ptr_channel(i));
+    }
+    assert_eq!(indexed.ptr_doorbell(), bytes.ptr_doorbell());
+
+    indexed.channel(2).write(7);
+    indexed.doorbell().write(9);
+    assert_eq!(buffer[8], 7);
+    assert_eq!(buffer[10], 9);
+}
@@ -0,0 +1,74 @@
+//! `#[derive(BaseAddress)]` for domain-specific base types, e.g. `#[base(0x4000_1000)] struct
+//! Uart1Base;`.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Fields};
+
+/// Implements `BaseAddress` returning the `#[base(...)]` constant of a unit struct, or
+/// forwarding to the only field of a newtype.
+pub fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let mut krate: syn::Path = syn::parse_quote!(::register_block);
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("base_address"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                krate = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported base_address option, expected `crate`"))
+            }
+        })?;
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let syn::Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "BaseAddress can only be derived for structs",
+        ));
+    };
+    let base_attr = input.attrs.iter().find(|attr| attr.path().is_ident("base"));
+    let body = match (&data.fields, base_attr) {
+        (Fields::Unit, Some(attr)) => {
+            let address: syn::Expr = attr.parse_args()?;
+            quote! { #address }
+        }
+        (Fields::Unit, None) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "a unit struct needs its address as `#[base(0x...)]` to derive BaseAddress",
+            ));
+        }
+        (fields, None) if fields.len() == 1 => {
+            let field = fields.iter().next().unwrap();
+            let member = match &field.ident {
+                Some(ident) => quote! { #ident },
+                None => quote! { 0 },
+            };
+            quote! { #krate::BaseAddress::base_address(self.#member) }
+        }
+        (_, Some(attr)) => {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`#[base(...)]` is only supported on unit structs",
+            ));
+        }
+        (fields, None) => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "BaseAddress can only be derived for unit structs and structs with one field",
+            ));
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics #krate::BaseAddress for #name #ty_generics #where_clause {
+            #[inline(always)]
+            fn base_address(self) -> usize {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,547 @@
+//! Bitfield declarations on register fields, e.g. `#[bits(name = "enable", range = 0..1)]`,
+//! and typed fields mapping a bit range to a user type, e.g.
+//! `#[field(name = "parity", range = 0..2, ty = Parity)]`, or to an enum generated from its
+//! values, e.g. `#[field(name = "mode", range = 0..2, values = [("Off", 0), ("On", 1)])]`.
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Attribute, Expr, Ident, Lit, RangeLimits, Type};
+
+/// A named bit range `lo..hi` within a register.
+pub struct BitField {
+    pub name: Ident,
+    pub lo: u32,
+    /// exclusive upper bit
+    pub hi: u32,
+    /// user type for `#[field]` declarations, converted via `TryFrom`/`Into` the raw bits
+    pub ty: Option<Type>,
+    /// named values of a `#[field(values = [...])]`, making `ty` an enum generated for them
+    pub values: Vec<EnumValue>,
+    pub span: Span,
+}
+
+/// A named value of a field with generated enum, e.g. `("Enabled", 1)`, optionally with a
+/// description as a third element like SVD's `enumeratedValues`.
+pub struct EnumValue {
+    pub name: Ident,
+    pub value: u128,
+    pub doc: Option<String>,
+}
+
+impl EnumValue {
+    /// Parses one `("Name", value)` or `("Name", value, "description")` tuple.
+    fn parse(expr: &Expr) -> syn::Result<Self> {
+        let invalid = || {
+            syn::Error::new_spanned(
+                expr,
+                "expected a value such as `(\"Enabled\", 1)` or `(\"Enabled\", 1, \"description\")`",
+            )
+        };
+        let Expr::Tuple(tuple) = expr else {
+            return Err(invalid());
+        };
+        let elems: Vec<_> = tuple.elems.iter().collect();
+        let lit = |expr: &Expr| match expr {
+            Expr::Lit(syn::ExprLit { lit, .. }) => Some(lit.clone()),
+            _ => None,
+        };
+        match elems.as_slice() {
+            [name, value, rest @ ..] if rest.len() <= 1 => {
+                let (Some(Lit::Str(name)), Some(Lit::Int(value))) = (lit(name), lit(value)) else {
+                    return Err(invalid());
+                };
+                let doc = match rest.first().map(|doc| lit(doc)) {
+                    None => None,
+                    Some(Some(Lit::Str(doc))) => Some(doc.value()),
+                    Some(_) => return Err(invalid()),
+                };
+                let ident = name.parse::<Ident>()?;
+                if ident == "Unknown" {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        "`Unknown` is reserved for the values without a name",
+                    ));
+                }
+                Ok(EnumValue {
+                    name: ident,
+                    value: value.base10_parse()?,
+                    doc,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Parses an integer literal inside a bit range expression.
+fn range_bound(expr: &Expr) -> syn::Result<u32> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(litint),
+            ..
+        }) => litint.base10_parse::<u32>(),
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "bit range bounds must be integer literals",
+        )),
+    }
+}
+
+/// For signed register types, the unsigned type of the same width, so that bitfields are
+/// masked on the raw bit pattern rather than through arithmetic shifts.
+pub fn unsigned_view(register_ty: &Type) -> Option<TokenStream> {
+    let Type::Path(path) = register_ty else {
+        return None;
+    };
+    let unsigned = match path.path.get_ident()?.to_string().as_str() {
+        "i8" => quote! { u8 },
+        "i16" => quote! { u16 },
+        "i32" => quote! { u32 },
+        "i64" => quote! { u64 },
+        "i128" => quote! { u128 },
+        "isize" => quote! { usize },
+        _ => return None,
+    };
+    Some(unsigned)
+}
+
+impl BitField {
+    /// Parses `#[bits(name = ..., range = ...)]` or `#[field(name = ..., range = ..., ty = ...)]`.
+    pub fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let typed = attr.path().is_ident("field");
+        let mut name = None;
+        let mut range = None;
+        let mut ty = None;
+        let mut values = Vec::new();
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                name = Some(litstr.parse::<Ident>()?);
+            } else if meta.path.is_ident("range") {
+                let expr: Expr = meta.value()?.parse()?;
+                let Expr::Range(expr_range) = &expr else {
+                    return Err(syn::Error::new_spanned(
+                        &expr,
+                        "expected a bit range such as `0..4` or `0..=3`",
+                    ));
+                };
+                let (Some(start), Some(end)) = (&expr_range.start, &expr_range.end) else {
+                    return Err(syn::Error::new_spanned(
+                        &expr,
+                        "bit ranges must have both a start and an end",
+                    ));
+                };
+                let lo = range_bound(start)?;
+                let hi = match expr_range.limits {
+                    RangeLimits::HalfOpen(_) => range_bound(end)?,
+                    RangeLimits::Closed(_) => range_bound(end)? + 1,
+                };
+                if hi <= lo {
+                    return Err(syn::Error::new_spanned(
+                        &expr,
+                        "bit range must not be empty",
+                    ));
+                }
+                range = Some((lo, hi));
+            } else if typed && meta.path.is_ident("ty") {
+                ty = Some(meta.value()?.parse()?);
+            } else if typed && meta.path.is_ident("values") {
+                let expr: Expr = meta.value()?.parse()?;
+                let Expr::Array(array) = &expr else {
+                    return Err(syn::Error::new_spanned(
+                        &expr,
+                        "expected a list of values such as `[(\"Disabled\", 0), (\"Enabled\", 1)]`",
+                    ));
+                };
+                for elem in &array.elems {
+                    let value = EnumValue::parse(elem)?;
+                    if values
+                        .iter()
+                        .any(|other: &EnumValue| other.name == value.name)
+                    {
+                        return Err(syn::Error::new_spanned(
+                            elem,
+                            format!("duplicate value name `{}`", value.name),
+                        ));
+                    }
+                    if values
+                        .iter()
+                        .any(|other: &EnumValue| other.value == value.value)
+                    {
+                        return Err(syn::Error::new_spanned(
+                            elem,
+                            format!("duplicate value {}", value.value),
+                        ));
+                    }
+                    values.push(value);
+                }
+            } else if typed {
+                return Err(meta.error(
+                    "unsupported field option, expected `name`, `range`, `ty` or `values`",
+                ));
+            } else {
+                return Err(meta.error("unsupported bits option, expected `name` or `range`"));
+            }
+            Ok(())
+        })?;
+        let missing = || {
+            let message = if typed {
+                "typed fields must have #[field(name = \"...\", range = lo..hi, ty = ...)] or #[field(name = \"...\", range = lo..hi, values = [...])]"
+            } else {
+                "bitfields must have #[bits(name = \"...\", range = lo..hi)]"
+            };
+            syn::Error::new_spanned(attr, message)
+        };
+        let name = name.ok_or_else(missing)?;
+        let (lo, hi) = range.ok_or_else(missing)?;
+        // the enum generated for the values is named after the field unless `ty` names it
+        if ty.is_none() && !values.is_empty() {
+            ty = Some(Type::Verbatim(
+                format_ident!("{}", crate::camel_case(&name.to_string())).into_token_stream(),
+            ));
+        }
+        if typed && ty.is_none() {
+            return Err(missing());
+        }
+        let field = BitField {
+            name,
+            lo,
+            hi,
+            ty,
+            values,
+            span: attr.pound_token.span,
+        };
+        if let Some(value) = field.values.iter().find(|value| value.value > field.max()) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "value `{}` ({}) doesn't fit in bits {}..{}",
+                    value.name, value.value, field.lo, field.hi
+                ),
+            ));
+        }
+        Ok(field)
+    }
+
+    /// The largest value the field can hold.
+    fn max(&self) -> u128 {
+        if self.len() >= 128 {
+            u128::MAX
+        } else {
+            (1u128 << self.len()) - 1
+        }
+    }
+
+    fn len(&self) -> u32 {
+        self.hi - self.lo
+    }
+
+    /// The mask of the field once shifted down to bit 0.
+    fn mask(&self) -> Literal {
+        Literal::u128_unsuffixed(self.max())
+    }
+
+    /// The smallest unsigned integer that fits the range.
+    fn raw_type(&self) -> TokenStream {
+        match self.len() {
+            0..=8 => quote! { u8 },
+            9..=16 => quote! { u16 },
+            17..=32 => quote! { u32 },
+            33..=64 => quote! { u64 },
+            _ => quote! { u128 },
+        }
+    }
+
+    /// The type returned by the getter of a plain bitfield: `bool` for single bits,
+    /// otherwise the smallest unsigned integer that fits the range.
+    fn value_type(&self) -> TokenStream {
+        match self.len() {
+            1 => quote! { bool },
+            2..=8 => quote! { u8 },
+            9..=16 => quote! { u16 },
+            17..=32 => quote! { u32 },
+            33..=64 => quote! { u64 },
+            _ => quote! { u128 },
+        }
+    }
+
+    pub fn getter(
+        &self,
+        register: &Ident,
+        register_ty: &Type,
+        index_param: &TokenStream,
+        ptr_type: &TokenStream,
+        init_expr: &TokenStream,
+    ) -> TokenStream {
+        let name = &self.name;
+        let lo = self.lo;
+        let mask = self.mask();
+        let value_ty = self.value_type();
+        let read = match unsigned_view(register_ty) {
+            Some(unsigned) => quote! { (reg.read() as #unsigned) },
+            None => quote! { reg.read() },
+        };
+        if let (Some(ty), false) = (&self.ty, self.values.is_empty()) {
+            let raw_ty = self.raw_type();
+            let doc = format!(
+                "Reads bits `{}..{}` of `{}` as a `{}`, with `Unknown` for values without a name.",
+                self.lo,
+                self.hi,
+                register,
+                quote!(#ty)
+            );
+            return quote! {
+                #[doc = #doc]
+                #[inline(always)]
+                pub fn #name(&self, #index_param) -> #ty {
+                    let reg: #ptr_type = #init_expr;
+                    <#ty as ::core::convert::From<#raw_ty>>::from(((#read >> #lo) & #mask) as #raw_ty)
+                }
+            };
+        }
+        if let Some(ty) = &self.ty {
+            let raw_ty = self.raw_type();
+            let doc = format!(
+                "Reads bits `{}..{}` of `{}` as a `{}`, or the `TryFrom` error if the bits don't map to a valid value.",
+                self.lo,
+                self.hi,
+                register,
+                quote!(#ty)
+            );
+            return quote! {
+                #[doc = #doc]
+                #[inline(always)]
+                pub fn #name(&self, #index_param) -> ::core::result::Result<#ty, <#ty as ::core::convert::TryFrom<#raw_ty>>::Error> {
+                    let reg: #ptr_type = #init_expr;
+                    <#ty as ::core::convert::TryFrom<#raw_ty>>::try_from(((#read >> #lo) & #mask) as #raw_ty)
+                }
+            };
+        }
+        let doc = format!("Reads bits `{}..{}` of `{}`.", self.lo, self.hi, register);
+        let extract = if self.len() == 1 {
+            quote! { (#read >> #lo) & #mask != 0 }
+        } else {
+            quote! { ((#read >> #lo) & #mask) as #value_ty }
+        };
+        quote! {
+            #[doc = #doc]
+            #[inline(always)]
+            pub fn #name(&self, #index_param) -> #value_ty {
+                let reg: #ptr_type = #init_expr;
+                #extract
+            }
+        }
+    }
+
+    pub fn setter(
+        &self,
+        register: &Ident,
+        register_ty: &Type,
+        index_param: &TokenStream,
+        ptr_type: &TokenStream,
+        init_expr: &TokenStream,
+        no_panic: bool,
+    ) -> TokenStream {
+        let setter = format_ident!("set_{}", self.name);
+        let lo = self.lo;
+        let mask = self.mask();
+        let doc = format!(
+            "Replaces bits `{}..{}` of `{}` with `value`, leaving the other bits untouched.",
+            self.lo, self.hi, register
+        );
+        let check = (self.checked() && !no_panic).then(|| {
+            let message = format!(
+                "{{}} doesn't fit in bitfield `{}` (bits {}..{})",
+                self.name, self.lo, self.hi
+            );
+            quote! { debug_assert!(value as u128 <= #mask, #message, value); }
+        });
+        let (value_ty, convert) = match &self.ty {
+            Some(ty) => {
+                let raw_ty = self.raw_type();
+                (
+                    quote! { #ty },
+                    quote! { let value: #raw_ty = value.into(); },
+                )
+            }
+            None => (self.value_type(), quote! {}),
+        };
+        let modify = match unsigned_view(register_ty) {
+            Some(unsigned) => quote! {
+                reg.modify(|old| (((old as #unsigned) & !(#mask << #lo)) | (((value as #unsigned) & #mask) << #lo)) as #register_ty);
+            },
+            None => quote! {
+                reg.modify(|old| (old & !(#mask << #lo)) | (((value as #register_ty) & #mask) << #lo));
+            },
+        };
+        quote! {
+            #[doc = #doc]
+            #[inline(always)]
+            pub fn #setter(&self, #index_param value: #value_ty) {
+                #check
+                #convert
+                let reg: #ptr_type = #init_expr;
+                #modify
+            }
+        }
+    }
+
+    /// The enum generated for the `values` of the field, if it has any, converting from and
+    /// into the raw bits.
+    pub fn values_enum(&self, register: &Ident, vis: &syn::Visibility) -> Option<TokenStream> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let ty = self.ty.as_ref()?;
+        let raw_ty = self.raw_type();
+        let doc = format!(
+            "Values of bits `{}..{}` of `{}`, as read by `{}()`.",
+            self.lo, self.hi, register, self.name
+        );
+        let variants = self.values.iter().map(|value| {
+            let name = &value.name;
+            let doc = match &value.doc {
+                Some(doc) => format!("`{}`: {}", value.value, doc),
+                None => format!("`{}`", value.value),
+            };
+            quote! {
+                #[doc = #doc]
+                #name,
+            }
+        });
+        let from_raw = self.values.iter().map(|value| {
+            let name = &value.name;
+            let raw = Literal::u128_unsuffixed(value.value);
+            quote! { #raw => #ty::#name, }
+        });
+        let into_raw = self.values.iter().map(|value| {
+            let name = &value.name;
+            let raw = Literal::u128_unsuffixed(value.value);
+            quote! { #ty::#name => #raw, }
+        });
+        Some(quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis enum #ty {
+                #(#variants)*
+                /// A value without a name, holding the raw bits.
+                Unknown(#raw_ty),
+            }
+            impl ::core::convert::From<#raw_ty> for #ty {
+                fn from(raw: #raw_ty) -> Self {
+                    // every raw value may have a name
+                    #[allow(unreachable_patterns)]
+                    match raw {
+                        #(#from_raw)*
+                        other => #ty::Unknown(other),
+                    }
+                }
+            }
+            impl ::core::convert::From<#ty> for #raw_ty {
+                fn from(value: #ty) -> Self {
+                    match value {
+                        #(#into_raw)*
+                        #ty::Unknown(raw) => raw,
+                    }
+                }
+            }
+        })
+    }
+
+    /// Whether the field is a multi-bit plain bitfield, whose setter can be handed values
+    /// that don't fit.
+    fn checked(&self) -> bool {
+        self.ty.is_none() && self.len() > 1
+    }
+
+    /// `try_set_<field>()` and `set_<field>_saturating()` of multi-bit plain bitfields, next
+    /// to the truncating setter.
+    pub fn checked_setters(
+        &self,
+        krate: &syn::Path,
+        index_param: &TokenStream,
+    ) -> Option<TokenStream> {
+        if !self.checked() {
+            return None;
+        }
+        let setter = format_ident!("set_{}", self.name);
+        let mask = self.mask();
+        let try_setter = format_ident!("try_set_{}", self.name);
+        let saturating_setter = format_ident!("set_{}_saturating", self.name);
+        let value_ty = self.value_type();
+        let index_arg = (!index_param.is_empty()).then(|| quote! { index, });
+        let try_doc = format!(
+            "Like `{}`, but fails instead of truncating values that don't fit in bits `{}..{}`.",
+            setter, self.lo, self.hi
+        );
+        let saturating_doc = format!(
+            "Like `{}`, but clamps values that don't fit in bits `{}..{}` to the largest one that does.",
+            setter, self.lo, self.hi
+        );
+        Some(quote! {
+            #[doc = #try_doc]
+            #[inline(always)]
+            pub fn #try_setter(
+                &self,
+                #index_param
+                value: #value_ty,
+            ) -> ::core::result::Result<(), #krate::FieldOverflow> {
+                if value as u128 > #mask {
+                    return ::core::result::Result::Err(#krate::FieldOverflow {
+                        value: value as u128,
+                        max: #mask,
+                    });
+                }
+                self.#setter(#index_arg value);
+                ::core::result::Result::Ok(())
+            }
+            #[doc = #saturating_doc]
+            #[inline(always)]
+            pub fn #saturating_setter(&self, #index_param value: #value_ty) {
+                self.#setter(#index_arg value.min(#mask as #value_ty))
+            }
+        })
+    }
+
+    /// For register types the macro cannot size itself, a const assertion that the range
+    /// fits, checked once the type is resolved.
+    pub fn width_assertion(&self, register: &Ident, register_ty: &Type) -> TokenStream {
+        let hi = self.hi as usize;
+        let message = format!(
+            "bitfield `{}` exceeds the width of register `{}`",
+            self.name, register
+        );
+        quote! {
+            const _: () = assert!(#hi <= ::core::mem::size_of::<#register_ty>() * 8, #message);
+        }
+    }
+}
+
+/// Checks that the bitfields of one register fit its width and don't overlap each other.
+pub fn check_layout(fields: &[BitField], width: Option<u32>) -> syn::Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        if let Some(width) = width {
+            if field.hi > width {
+                return Err(syn::Error::new(
+                    field.span,
+                    format!(
+                        "bitfield `{}` (bits {}..{}) exceeds the {}-bit register width",
+                        field.name, field.lo, field.hi, width
+                    ),
+                ));
+            }
+        }
+        if let Some(other) = fields[..i]
+            .iter()
+            .find(|other| field.lo < other.hi && other.lo < field.hi)
+        {
+            return Err(syn::Error::new(
+                field.span,
+                format!(
+                    "bitfield `{}` (bits {}..{}) overlaps bitfield `{}` (bits {}..{})",
+                    field.name, field.lo, field.hi, other.name, other.lo, other.hi
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
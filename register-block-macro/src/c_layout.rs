@@ -0,0 +1,84 @@
+//! `#[register_block(c_layout)]`: a `#[repr(C)]` struct mirroring the register map, with
+//! padding for the gaps between registers, for FFI and pointer casts.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, Ident};
+
+/// A register of the block, as a field of the layout struct.
+pub struct Entry<'a> {
+    pub name: Ident,
+    /// the field type, `[T; N]` for arrays
+    pub ty: TokenStream,
+    pub offset: u32,
+    pub end: u32,
+    pub docs: Vec<&'a Attribute>,
+}
+
+/// The `<Block>Layout` struct holding `entries`, with offset assertions checked once the
+/// field types are resolved.
+pub fn generate(
+    layout_ty: &Ident,
+    vis: &syn::Visibility,
+    block: &Ident,
+    mut entries: Vec<Entry>,
+) -> syn::Result<TokenStream> {
+    entries.sort_by_key(|entry| entry.offset);
+    let mut fields = Vec::new();
+    let mut asserts = Vec::new();
+    let mut cursor = 0;
+    let mut previous: Option<&Ident> = None;
+    for entry in &entries {
+        if let (Some(previous), true) = (previous, entry.offset < cursor) {
+            return Err(syn::Error::new_spanned(
+                &entry.name,
+                format!(
+                    "`{}` overlaps `{}`, which a `#[repr(C)]` layout can't express",
+                    entry.name, previous
+                ),
+            ));
+        }
+        if entry.offset > cursor {
+            let padding = format_ident!("_reserved_{:02x}", cursor);
+            let len = (entry.offset - cursor) as usize;
+            fields.push(quote! { #padding: [u8; #len], });
+        }
+        let Entry {
+            name,
+            ty,
+            offset,
+            docs,
+            ..
+        } = entry;
+        let offset = *offset as usize;
+        let message = format!(
+            "`{}` doesn't end up at its declared offset in `{}`",
+            name, layout_ty
+        );
+        fields.push(quote! {
+            #(#docs)*
+            pub #name: #ty,
+        });
+        asserts.push(quote! {
+            assert!(::core::mem::offset_of!(#layout_ty, #name) == #offset, #message);
+        });
+        cursor = entry.end;
+        previous = Some(name);
+    }
+    let doc = format!(
+        "The registers of [`{}`] as a `#[repr(C)]` struct, with padding for the gaps between them.",
+        block
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        ///
+        /// Accesses through a reference to it aren't volatile; use the block's accessors for
+        /// MMIO and this struct for FFI and pointer casts.
+        #[repr(C)]
+        #vis struct #layout_ty {
+            #(#fields)*
+        }
+        const _: () = {
+            #(#asserts)*
+        };
+    })
+}
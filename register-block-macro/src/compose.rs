@@ -0,0 +1,139 @@
+//! Values split across several registers, e.g.
+//! `#[compose(name = "count", parts = [count_lo, count_hi], ty = u64)]` on the struct.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, Ident, Token, Type};
+
+use crate::init::{self, Target};
+
+/// A composite read of `parts`, least significant part first.
+pub struct Compose {
+    name: Ident,
+    parts: Vec<Ident>,
+    ty: Type,
+}
+
+/// Parses every `#[compose(name = ..., parts = [...], ty = ...)]` attribute.
+pub fn parse(attrs: &[Attribute]) -> syn::Result<Vec<Compose>> {
+    let mut composes = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("compose")) {
+        let mut name = None;
+        let mut parts = None;
+        let mut ty = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                name = Some(litstr.parse::<Ident>()?);
+            } else if meta.path.is_ident("parts") {
+                let content;
+                let value = meta.value()?;
+                syn::bracketed!(content in value);
+                let list = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                parts = Some(list.into_iter().collect::<Vec<_>>());
+            } else if meta.path.is_ident("ty") {
+                ty = Some(meta.value()?.parse()?);
+            } else {
+                return Err(
+                    meta.error("unsupported compose option, expected `name`, `parts` or `ty`")
+                );
+            }
+            Ok(())
+        })?;
+        let (Some(name), Some(parts), Some(ty)) = (name, parts, ty) else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "composite reads must have #[compose(name = \"...\", parts = [...], ty = ...)]",
+            ));
+        };
+        if parts.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`parts` must name at least one register",
+            ));
+        }
+        composes.push(Compose { name, parts, ty });
+    }
+    Ok(composes)
+}
+
+impl Compose {
+    /// Generates `read_<name>()`, reading the parts in the order listed and placing each part
+    /// above the bits of the ones before it.
+    pub fn reader(&self, targets: &[Target]) -> syn::Result<TokenStream> {
+        let ty = &self.ty;
+        let mut reads = Vec::new();
+        for part in &self.parts {
+            let target = init::find(targets, part, "compose")?;
+            if let Some(reason) = target.unreadable {
+                return Err(syn::Error::new_spanned(
+                    part,
+                    format!("#[compose] can't read `{}`: {}", part, reason),
+                ));
+            }
+            let cfg = &target.cfg;
+            let part_ty = &target.ty;
+            let ptr_type = &target.ptr_type;
+            let init_expr = &target.init_expr;
+            reads.push(quote! {
+                #cfg
+                {
+                    let reg: #ptr_type = #init_expr;
+                    value |= (reg.read() as #ty) << shift;
+                    shift += ::core::mem::size_of::<#part_ty>() * 8;
+                }
+            });
+        }
+        let read_fn = format_ident!("read_{}", self.name);
+        let names: Vec<_> = self
+            .parts
+            .iter()
+            .map(|part| format!("`{}`", part))
+            .collect();
+        let doc = format!(
+            "Reads {} in that order and assembles them into one value, least significant part first.",
+            names.join(", ")
+        );
+        Ok(quote! {
+            #[doc = #doc]
+            #[allow(unused_assignments)]
+            pub fn #read_fn(&self) -> #ty {
+                let mut value: #ty = 0;
+                let mut shift = 0;
+                #(#reads)*
+                value
+            }
+        })
+    }
+
+    /// A `const` item failing to compile if the parts together are wider than `ty`, which
+    /// would shift the last ones out of the value.
+    pub fn width_assertion(&self, targets: &[Target]) -> syn::Result<TokenStream> {
+        let ty = &self.ty;
+        let mut sizes = Vec::new();
+        for part in &self.parts {
+            let target = init::find(targets, part, "compose")?;
+            let cfg = &target.cfg;
+            let part_ty = &target.ty;
+            sizes.push(quote! {
+                #cfg
+                {
+                    size += ::core::mem::size_of::<#part_ty>();
+                }
+            });
+        }
+        let message = format!(
+            "the parts of `{}` don't fit in a `{}`",
+            self.name,
+            quote!(#ty)
+        );
+        Ok(quote_spanned! {ty.span()=>
+            const _: () = {
+                let mut size = 0;
+                #(#sizes)*
+                assert!(size <= ::core::mem::size_of::<#ty>(), #message);
+            };
+        })
+    }
+}
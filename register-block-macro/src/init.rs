@@ -0,0 +1,85 @@
+//! Power-on sequences, e.g. `#[init(ctrl = 0x1, mode = 0x2)]` on the struct.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Ident, MetaNameValue, Token, Type};
+
+/// A register that an init sequence, a composite read or a snapshot may refer to.
+pub struct Target {
+    pub name: Ident,
+    /// `#[cfg]` attributes of the register, which gate its accesses too
+    pub cfg: TokenStream,
+    pub ty: Type,
+    pub offset: u32,
+    /// size in bytes of a plain native-endian integer register, which a wider access
+    /// covering its neighbours can read too
+    pub bytes: Option<u32>,
+    /// why the register can't be written by an init sequence, if it can't
+    pub unwritable: Option<&'static str>,
+    /// why the register can't be part of a composite read, if it can't
+    pub unreadable: Option<&'static str>,
+    pub ptr_type: TokenStream,
+    pub init_expr: TokenStream,
+}
+
+/// Parses every `#[init(...)]` attribute into its `register = value` writes, in order.
+pub fn parse(attrs: &[Attribute]) -> syn::Result<Vec<(Ident, Expr)>> {
+    let mut writes = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("init")) {
+        let list = attr.meta.require_list()?;
+        let pairs =
+            Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(list.tokens.clone())?;
+        for pair in pairs {
+            let name = pair.path.require_ident()?.clone();
+            writes.push((name, pair.value));
+        }
+    }
+    Ok(writes)
+}
+
+/// Generates `apply_init()`, writing `writes` to their registers in order.
+pub fn apply_init(writes: &[(Ident, Expr)], targets: &[Target]) -> syn::Result<TokenStream> {
+    let mut stmts = Vec::new();
+    for (name, value) in writes {
+        let target = find(targets, name, "init")?;
+        if let Some(reason) = target.unwritable {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!("#[init] can't write `{}`: {}", name, reason),
+            ));
+        }
+        let cfg = &target.cfg;
+        let ptr_type = &target.ptr_type;
+        let init_expr = &target.init_expr;
+        stmts.push(quote! {
+            #cfg
+            {
+                let reg: #ptr_type = #init_expr;
+                reg.write(#value);
+            }
+        });
+    }
+    Ok(quote! {
+        /// Writes the init sequence declared with `#[init(...)]`, in declaration order.
+        pub fn apply_init(&self) {
+            #(#stmts)*
+        }
+    })
+}
+
+/// Looks up the register `name` refers to, for the `#[attribute]` it appears in.
+pub fn find<'a>(targets: &'a [Target], name: &Ident, attribute: &str) -> syn::Result<&'a Target> {
+    targets
+        .iter()
+        .find(|target| target.name == *name)
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                name,
+                format!(
+                    "#[{}] names `{}`, which is not a register of this block",
+                    attribute, name
+                ),
+            )
+        })
+}
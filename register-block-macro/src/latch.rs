@@ -0,0 +1,69 @@
+//! Registers that only hold a fresh value after a trigger write, e.g.
+//! `#[register(offset = 0x10, access = "RO", latch_via = "capture")]` on a timer capture.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Expr, Ident, LitStr};
+
+use crate::init::Target;
+
+/// A read of `register` preceded by writing `value` to `trigger`.
+pub struct Latch {
+    pub register: Ident,
+    pub trigger: LitStr,
+    /// `latch_value`, 1 if not given
+    pub value: Option<Expr>,
+    pub cfg: TokenStream,
+    pub ty: syn::Type,
+    pub ptr_type: TokenStream,
+    pub init_expr: TokenStream,
+}
+
+impl Latch {
+    /// Generates `read_<register>_latched()`, which writes the trigger and then reads the
+    /// register.
+    pub fn reader(&self, targets: &[Target]) -> syn::Result<TokenStream> {
+        let trigger_name = self.trigger.parse::<Ident>()?;
+        let Some(trigger) = targets.iter().find(|target| target.name == trigger_name) else {
+            return Err(syn::Error::new_spanned(
+                &self.trigger,
+                format!(
+                    "`latch_via` names `{}`, which is not a register of this block",
+                    trigger_name
+                ),
+            ));
+        };
+        if let Some(reason) = trigger.unwritable {
+            return Err(syn::Error::new_spanned(
+                &self.trigger,
+                format!("`{}` can't trigger a latch: {}", trigger_name, reason),
+            ));
+        }
+        let value = match &self.value {
+            Some(value) => quote! { #value },
+            None => quote! { 1 },
+        };
+        let cfg = &self.cfg;
+        let trigger_cfg = &trigger.cfg;
+        let ty = &self.ty;
+        let ptr_type = &self.ptr_type;
+        let init_expr = &self.init_expr;
+        let trigger_ptr_type = &trigger.ptr_type;
+        let trigger_init_expr = &trigger.init_expr;
+        let read_fn = format_ident!("read_{}_latched", self.register);
+        let doc = format!(
+            "Writes `{}` to latch a fresh value into `{}`, then reads it.",
+            trigger_name, self.register
+        );
+        Ok(quote! {
+            #cfg
+            #trigger_cfg
+            #[doc = #doc]
+            pub fn #read_fn(&self) -> #ty {
+                let trigger: #trigger_ptr_type = #trigger_init_expr;
+                trigger.write(#value);
+                let reg: #ptr_type = #init_expr;
+                reg.read()
+            }
+        })
+    }
+}
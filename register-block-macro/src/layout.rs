@@ -0,0 +1,217 @@
+//! Bookkeeping of the byte ranges claimed by registers, for overlap checks.
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+use syn::spanned::Spanned;
+use syn::Attribute;
+
+/// A byte range `[start, end)` claimed by a register, register array or reserved range.
+pub struct Claim {
+    pub start: u32,
+    pub end: u32,
+    /// whether the claim occupies the read side of its range
+    pub reads: bool,
+    /// whether the claim occupies the write side of its range
+    pub writes: bool,
+    pub name: String,
+    pub reserved: bool,
+    /// the `#[cfg]` predicate of the field, if it is conditional
+    pub cfg: Option<TokenStream>,
+    /// where errors about the claim point
+    pub span: Span,
+}
+
+/// The bytes from `start` of a sub-block, whose end is only known once its `BLOCK_SIZE` is.
+pub struct SubBlockClaim {
+    pub start: u32,
+    /// `start` plus the sub-block's `BLOCK_SIZE`, as a `usize` expression
+    pub end: TokenStream,
+    pub name: String,
+    pub cfg: Option<TokenStream>,
+    pub span: Span,
+}
+
+#[derive(Default)]
+pub struct Layout {
+    claims: Vec<Claim>,
+    sub_blocks: Vec<SubBlockClaim>,
+    /// the highest offset any claim may cover, from `#[register_block(max_offset = ...)]`
+    pub max_offset: Option<u32>,
+    /// `compile_error!`s for collisions between claims under different cfgs, gated on both
+    pub conditional_errors: Vec<TokenStream>,
+}
+
+impl Layout {
+    /// Records `claim`, or returns an error message if it collides with an earlier claim.
+    ///
+    /// A read-side claim may cover exactly the same bytes as a write-side claim (e.g. RO
+    /// over WO), but may not share any bytes with another claim on the same side. Reserved
+    /// ranges occupy both sides.
+    ///
+    /// Claims under different `#[cfg]`s may only exist together, e.g. registers of two chip
+    /// revisions sharing an offset, so their collisions become `conditional_errors` that only
+    /// fire once both cfgs hold.
+    pub fn claim(&mut self, claim: Claim) -> Result<(), String> {
+        if let Some(max) = self.max_offset {
+            if u64::from(claim.end) > u64::from(max) + 1 {
+                return Err(format!(
+                    "`{}` at 0x{:X}..0x{:X} extends past the max_offset of 0x{:X}.",
+                    claim.name, claim.start, claim.end, max
+                ));
+            }
+        }
+        let collisions = self.claims.iter().filter(|other| {
+            let same_side = (other.reads && claim.reads) || (other.writes && claim.writes);
+            let same_bytes = other.start == claim.start && other.end == claim.end;
+            claim.start < other.end && other.start < claim.end && (same_side || !same_bytes)
+        });
+        let mut conditional = Vec::new();
+        for other in collisions {
+            let message = collision_message(&claim, other);
+            if same_cfg(&claim.cfg, &other.cfg) {
+                return Err(message);
+            }
+            let cfgs = claim.cfg.iter().chain(&other.cfg);
+            conditional.push(quote_spanned! {claim.span=>
+                #[cfg(all(#(#cfgs),*))]
+                ::core::compile_error!(#message);
+            });
+        }
+        self.conditional_errors.extend(conditional);
+        self.claims.push(claim);
+        Ok(())
+    }
+
+    /// Claims the range of a `#[reserved(offset = ..., len = ...)]` attribute, under the
+    /// `#[cfg]` predicate `cfg` of its field, returning its start and end.
+    pub fn reserve(
+        &mut self,
+        attr: &Attribute,
+        cfg: Option<TokenStream>,
+    ) -> syn::Result<(u32, u32)> {
+        let claim = Claim {
+            cfg,
+            ..parse_reserved(attr)?
+        };
+        let range = (claim.start, claim.end);
+        self.claim(claim)
+            .map_err(|message| syn::Error::new_spanned(attr, message))?;
+        Ok(range)
+    }
+
+    /// Records the range of a sub-block, checked against every other claim by
+    /// [`Layout::sub_block_checks`] once all are known.
+    pub fn claim_sub_block(&mut self, claim: SubBlockClaim) {
+        self.sub_blocks.push(claim);
+    }
+
+    /// `const` assertions failing to compile if a sub-block overlaps a register, reserved
+    /// range, window or another sub-block, each gated on the cfgs of both.
+    ///
+    /// Sub-blocks occupy both sides of their bytes, like reserved ranges.
+    pub fn sub_block_checks(&self) -> Vec<TokenStream> {
+        let mut checks = Vec::new();
+        for (index, sub_block) in self.sub_blocks.iter().enumerate() {
+            let SubBlockClaim {
+                start, end, span, ..
+            } = sub_block;
+            let start = *start as usize;
+            // claims ending before the sub-block can't overlap it, the rest must start after it
+            for other in self
+                .claims
+                .iter()
+                .filter(|other| sub_block.start < other.end)
+            {
+                let message = format!(
+                    "sub-block `{}` at 0x{:X} overlaps `{}` at 0x{:X}..0x{:X}",
+                    sub_block.name, sub_block.start, other.name, other.start, other.end
+                );
+                let other_start = other.start as usize;
+                let cfgs = sub_block.cfg.iter().chain(&other.cfg);
+                checks.push(quote_spanned! {*span=>
+                    #[cfg(all(#(#cfgs),*))]
+                    const _: () = assert!(#end <= #other_start, #message);
+                });
+            }
+            for other in &self.sub_blocks[..index] {
+                let message = format!(
+                    "sub-block `{}` at 0x{:X} overlaps the sub-block `{}` at 0x{:X}",
+                    sub_block.name, sub_block.start, other.name, other.start
+                );
+                let other_start = other.start as usize;
+                let other_end = &other.end;
+                let cfgs = sub_block.cfg.iter().chain(&other.cfg);
+                checks.push(quote_spanned! {*span=>
+                    #[cfg(all(#(#cfgs),*))]
+                    const _: () = assert!(
+                        !(#start < #other_end && #other_start < #end),
+                        #message
+                    );
+                });
+            }
+        }
+        checks
+    }
+}
+
+/// Whether two claims exist under the same conditions, comparing their cfgs as spelled.
+fn same_cfg(a: &Option<TokenStream>, b: &Option<TokenStream>) -> bool {
+    a.as_ref().map(ToString::to_string) == b.as_ref().map(ToString::to_string)
+}
+
+/// The error for `claim` colliding with the earlier `other`.
+fn collision_message(claim: &Claim, other: &Claim) -> String {
+    if other.reserved || claim.reserved {
+        let (register, reserved) = if claim.reserved {
+            (other, claim)
+        } else {
+            (claim, other)
+        };
+        format!(
+            "Register `{}` at offset 0x{:X} lies inside the reserved range 0x{:X}..0x{:X}.",
+            register.name, register.start, reserved.start, reserved.end
+        )
+    } else if other.start == claim.start && other.end == claim.end {
+        format!("Duplicate register offset 0x{:X} for field `{}` (already used by `{}`). Only read-side (RO/RC) and write-side (WO/WC/W1C) fields may overlap.", claim.start, claim.name, other.name)
+    } else {
+        format!("Register `{}` at 0x{:X}..0x{:X} overlaps `{}` at 0x{:X}..0x{:X}. Only read-side (RO/RC) and write-side (WO/WC/W1C) fields covering the same bytes may overlap.", claim.name, claim.start, claim.end, other.name, other.start, other.end)
+    }
+}
+
+fn parse_reserved(attr: &Attribute) -> syn::Result<Claim> {
+    let mut offset = None;
+    let mut len = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("offset") {
+            let litint: syn::LitInt = meta.value()?.parse()?;
+            offset = Some(litint.base10_parse::<u32>()?);
+        } else if meta.path.is_ident("len") {
+            let litint: syn::LitInt = meta.value()?.parse()?;
+            len = Some((litint.base10_parse::<u32>()?, litint));
+        } else {
+            return Err(meta.error("unsupported reserved option, expected `offset` or `len`"));
+        }
+        Ok(())
+    })?;
+    let (Some(start), Some((len, len_lit))) = (offset, len) else {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "reserved ranges must have #[reserved(offset = ..., len = ...)]",
+        ));
+    };
+    let Some(end) = start.checked_add(len) else {
+        return Err(syn::Error::new_spanned(
+            len_lit,
+            "reserved range reaches past the end of the 32-bit address range",
+        ));
+    };
+    Ok(Claim {
+        start,
+        end,
+        reads: true,
+        writes: true,
+        name: String::from("reserved"),
+        reserved: true,
+        cfg: None,
+        span: attr.span(),
+    })
+}
@@ -1,11 +1,396 @@
 //! Procedural macro to generate UART register block and accessors.
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, ItemStruct, Lit};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse_macro_input, ItemStruct};
 
+mod base;
+mod bits;
+mod c_layout;
+mod compose;
+mod init;
+mod latch;
+mod layout;
+mod snapshot;
+mod subblock;
+mod verify;
+mod window;
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug)]
+enum Access {
+    RW,
+    RO,
+    WO,
+    WC,
+    RC,
+    W1C,
+    RW1C,
+}
+
+impl Access {
+    /// Parses an `access = "..."` string, ignoring case and any `-`, `_`, `/` or spaces, so
+    /// that e.g. `"read-only"`, `"ReadOnly"` and `"r/o"` all mean `RO`.
+    fn parse(value: &str) -> Option<Access> {
+        let normalized: String = value
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | '/' | ' '))
+            .collect::<String>()
+            .to_uppercase();
+        Some(match normalized.as_str() {
+            "RW" | "READWRITE" => Access::RW,
+            "RO" | "READONLY" => Access::RO,
+            "WO" | "WRITEONLY" => Access::WO,
+            "WC" | "CLEAR" | "WRITECLEAR" => Access::WC,
+            "RC" | "READCLEAR" => Access::RC,
+            "W1C" => Access::W1C,
+            "RW1C" => Access::RW1C,
+            _ => return None,
+        })
+    }
+    /// Whether accessing the register reads from the bus.
+    fn reads(self) -> bool {
+        matches!(
+            self,
+            Access::RW | Access::RO | Access::RC | Access::W1C | Access::RW1C
+        )
+    }
+    /// Whether accessing the register writes to the bus.
+    fn writes(self) -> bool {
+        matches!(
+            self,
+            Access::RW | Access::WO | Access::WC | Access::W1C | Access::RW1C
+        )
+    }
+    /// Whether the field occupies the read side of its offset for overlap checks.
+    ///
+    /// W1C registers are readable, but are acknowledged by writing, so for collisions they
+    /// count as write-side only and may share an offset with a RO view. RW1C registers are
+    /// the same on the bus, but claim both sides.
+    fn read_side(self) -> bool {
+        self.reads() && !matches!(self, Access::W1C)
+    }
+}
+
+/// Bit width of a primitive integer type, if the macro can tell from the type alone.
+///
+/// `usize`/`isize` and type aliases depend on the target or on name resolution, so they
+/// return `None` and any width checks are deferred to a generated const assertion.
+fn primitive_width(ty: &syn::Type) -> Option<u32> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let ident = path.path.get_ident()?.to_string();
+    match ident.as_str() {
+        "u8" | "i8" => Some(8),
+        "u16" | "i16" => Some(16),
+        "u32" | "i32" => Some(32),
+        "u64" | "i64" => Some(64),
+        "u128" | "i128" => Some(128),
+        _ => None,
+    }
+}
+/// The byte offset of `litint` units of `unit` bytes each, for `word_size` blocks.
+fn scale(litint: &syn::LitInt, unit: u32) -> syn::Result<u32> {
+    litint
+        .base10_parse::<u32>()?
+        .checked_mul(unit)
+        .ok_or_else(|| syn::Error::new_spanned(litint, "byte offset out of range"))
+}
+/// The error for a `#[reserved]` or `#[subblock]` attribute in a `word_size` block, whose byte
+/// offsets can't be mixed with the register indices.
+fn mixed_offsets(attr: &syn::Attribute) -> proc_macro2::TokenStream {
+    let message = format!(
+        "`#[{}]` offsets are in bytes, which can't be mixed with the register indices of a `word_size` block",
+        attr.path().get_ident().unwrap()
+    );
+    syn::Error::new_spanned(attr, message).to_compile_error()
+}
+/// Keys accepted by `#[register(...)]`, for diagnostics.
+const REGISTER_KEYS: &str = "`offset`, `access`, `count`, `stride`, `width`, `word_order`, `reset`, `clear_value`, `endian`, `fifo`, `shadow`, `latch_via`, `latch_value`, `cs`, `unchecked` and `allow_any_type`";
+
+/// `snake_case` to `CamelCase`, for the names of generated types.
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+/// Whether `ty` is spelled as one of the unsigned integer types implementing `Bits`.
+fn is_unsigned(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+    path.path.get_ident().is_some_and(|ident| {
+        matches!(
+            ident.to_string().as_str(),
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize"
+        )
+    })
+}
+/// The address `offset` bytes past `base`. Overflowing the address space panics in debug
+/// builds, except in `no_panic` blocks, which wrap around like release builds.
+fn offset_address(
+    krate: &syn::Path,
+    no_panic: bool,
+    base: proc_macro2::TokenStream,
+    offset: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if no_panic {
+        quote! { (#base).wrapping_add(#offset) }
+    } else {
+        quote! { #krate::__private::offset_address(#base, #offset) }
+    }
+}
+/// The first line of the doc comment in `attrs`, made safe for a markdown table cell.
+fn first_doc_line(attrs: &[&syn::Attribute]) -> String {
+    attrs
+        .iter()
+        .find_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) => Some(doc.value().trim().replace('|', "\\|")),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+/// The condition of the `#[cfg]` attributes in `attrs`, `None` if there are none.
+fn cfg_predicate(attrs: &[&syn::Attribute]) -> Option<proc_macro2::TokenStream> {
+    let predicates: Vec<_> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .map(|list| list.tokens.clone())
+        .collect();
+    match predicates.as_slice() {
+        [] => None,
+        [predicate] => Some(predicate.clone()),
+        _ => Some(quote! { all(#(#predicates),*) }),
+    }
+}
+/// A statement raising `size` to `end` in the const block computing `BLOCK_SIZE`.
+fn block_end(end: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            let end = #end;
+            if end > size {
+                size = end;
+            }
+        }
+    }
+}
 /// Attribute macro to generate register block and accessors for UART.
+///
+/// By default every field becomes an accessor named after the field that returns the
+/// matching pointer wrapper (`RW`, `RO`, ...). With `#[register_block(flat)]` the
+/// block instead gets flat `read_<field>`, `write_<field>`, `modify_<field>` and
+/// `clear_<field>` methods.
+///
+/// `#[bits(name = "enable", range = 0..1)]` on a RW or RO register adds a getter (and for RW a
+/// setter) for that bit range. Single bits are `bool`, wider ranges the smallest unsigned
+/// integer that fits. On signed registers the ranges are taken from the raw two's complement
+/// pattern and returned zero-extended, so a multi-bit field holding a signed quantity must be
+/// sign-extended by the caller, e.g. `delta() as i8` for an 8-bit field.
+///
+/// `#[register_block(group)]` declares a group of register blocks behind the same base: every
+/// field without a `#[register]` attribute is a register block type whose accessor hands out
+/// that block at the parent's base.
+///
+/// `#[register_block(base = 0x4000_0000)]` fixes the address of a single-instance peripheral:
+/// `at_const()` creates the block on a `ConstantAddress` there, without any arguments.
+///
+/// `#[register_block(instances = N, stride = S)]` declares `N` copies of the block `S` bytes
+/// apart, reachable through `instance(i)`.
+///
+/// `#[register_block(no_panic)]` leaves out the index checks of register arrays and
+/// instances, and makes `dump()` do nothing when its buffer is too short, so the generated
+/// accessors have no panicking paths of their own. The debug assertions of the wrapper
+/// constructors, which catch a misaligned base, stay in debug builds, as a misaligned access
+/// would be undefined behaviour.
+///
+/// `#[register_block(flat, newtypes)]` gives every register a `<Field>Value` newtype, which
+/// its flat accessors return and take instead of the bare integer.
+///
+/// `#[register_block(max_offset = 0xFFFF)]` rejects registers, arrays and reserved ranges
+/// reaching past that offset, to catch typos in large register maps.
+///
+/// `#[register_block(derive_debug)]` additionally implements `Debug` for the block, printing
+/// the current value of every register that can be read without side effects.
+///
+/// `#[register_block(must_use)]` marks the flat `read_<field>` methods and bitfield getters
+/// `#[must_use]`, so ignored reads are warned about. Flat reads of RC registers always are.
+///
+/// `#[register_block(snapshot)]` generates a plain `<Block>Snapshot` struct holding the value
+/// of every such register, and a `snapshot()` method reading them all in field order.
+///
+/// `#[register_block(c_layout)]` generates a `#[repr(C)]` `<Block>Layout` struct with one field
+/// per register at its declared offset and padding in between, for FFI and pointer casts.
+///
+/// `#[register_block(word_size = 4)]` makes the `offset` and `stride` of every register count
+/// registers of that many bytes instead of bytes, for datasheets that number their registers.
+///
+/// `#[window(offset = 0x1000, len = 0x1000)]` on a field declares a memory-mapped buffer such
+/// as a shared SRAM, accessed in byte ranges with `<field>_read` and `<field>_write`.
+///
+/// `#[register_block(exclusive)]` makes `new` unsafe and adds `from_token`, which builds the
+/// block from a `BaseToken` that can only be taken once per address.
+///
+/// `#[register_block(verify = [(0x00, 4), (0x04, 4)])]` fails to compile unless every register,
+/// in declaration order, has the offset and width in bytes of its row in the table. Register
+/// arrays need their `count` in the row too, and their `stride` unless it is the width:
+/// `(0x10, 4, count = 4, stride = 8)`.
+///
+/// `#[register_block(lock)]` gives the block a `SpinLock`, or with `lock = path::Lock` a lock of
+/// another `RawLock` type, and a `locked(|block| ...)` method holding it around the closure.
+/// The `lock` feature of `register-block` provides both traits.
+///
+/// `#[register_block(crate = path)]` makes the generated code refer to the crate through
+/// `path` instead of `::register_block`, for renamed or re-exported dependencies.
 #[proc_macro_attribute]
-pub fn register_block(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn register_block(attr: TokenStream, item: TokenStream) -> TokenStream {
+    // Parse the block-level options
+    let mut flat = false;
+    let mut derive_debug = false;
+    let mut snapshot = false;
+    let mut must_use = false;
+    let mut group = false;
+    let mut no_panic = false;
+    let mut c_layout = false;
+    // `max_offset = N`: no register may reach past offset N
+    let mut max_offset: Option<u32> = None;
+    // the `newtypes` flag, kept for its span
+    let mut newtypes: Option<syn::Path> = None;
+    // the `exclusive` flag, kept for its span
+    let mut exclusive: Option<syn::Path> = None;
+    // `base = 0x...`: the fixed address of a single-instance peripheral
+    let mut fixed_base: Option<usize> = None;
+    // `word_size = N`: register offsets and strides count N-byte words
+    let mut word_size: Option<u32> = None;
+    // `instances = N, stride = S`: N copies of the block, S bytes apart
+    let mut instances: Option<(syn::LitInt, usize)> = None;
+    let mut instance_stride: Option<(syn::LitInt, usize)> = None;
+    // `verify = [(offset, width), ...]`: the reviewed layout, and the option for its span
+    let mut verify: Option<(syn::Path, Vec<verify::Row>)> = None;
+    // `lock` or `lock = Type`: the `RawLock` the block holds around `locked(...)`
+    let mut lock: Option<Option<syn::Type>> = None;
+    // `crate = path`: where the generated code finds this crate, for renamed dependencies
+    let mut krate: syn::Path = syn::parse_quote!(::register_block);
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("flat") {
+            flat = true;
+            Ok(())
+        } else if meta.path.is_ident("derive_debug") {
+            derive_debug = true;
+            Ok(())
+        } else if meta.path.is_ident("snapshot") {
+            snapshot = true;
+            Ok(())
+        } else if meta.path.is_ident("must_use") {
+            must_use = true;
+            Ok(())
+        } else if meta.path.is_ident("group") {
+            group = true;
+            Ok(())
+        } else if meta.path.is_ident("no_panic") {
+            no_panic = true;
+            Ok(())
+        } else if meta.path.is_ident("c_layout") {
+            c_layout = true;
+            Ok(())
+        } else if meta.path.is_ident("exclusive") {
+            exclusive = Some(meta.path.clone());
+            Ok(())
+        } else if meta.path.is_ident("newtypes") {
+            newtypes = Some(meta.path.clone());
+            Ok(())
+        } else if meta.path.is_ident("max_offset") {
+            let litint: syn::LitInt = meta.value()?.parse()?;
+            max_offset = Some(litint.base10_parse()?);
+            Ok(())
+        } else if meta.path.is_ident("base") {
+            let litint: syn::LitInt = meta.value()?.parse()?;
+            fixed_base = Some(litint.base10_parse()?);
+            Ok(())
+        } else if meta.path.is_ident("word_size") {
+            let litint: syn::LitInt = meta.value()?.parse()?;
+            let value: u32 = litint.base10_parse()?;
+            if !value.is_power_of_two() {
+                return Err(syn::Error::new_spanned(
+                    litint,
+                    "`word_size` must be a power of two",
+                ));
+            }
+            word_size = Some(value);
+            Ok(())
+        } else if meta.path.is_ident("instances") {
+            let litint: syn::LitInt = meta.value()?.parse()?;
+            let value = litint.base10_parse()?;
+            instances = Some((litint, value));
+            Ok(())
+        } else if meta.path.is_ident("stride") {
+            let litint: syn::LitInt = meta.value()?.parse()?;
+            let value = litint.base10_parse()?;
+            instance_stride = Some((litint, value));
+            Ok(())
+        } else if meta.path.is_ident("lock") {
+            lock = Some(if meta.input.peek(syn::Token![=]) {
+                Some(meta.value()?.parse()?)
+            } else {
+                None
+            });
+            Ok(())
+        } else if meta.path.is_ident("verify") {
+            verify = Some((meta.path.clone(), verify::parse(meta.value()?)?));
+            Ok(())
+        } else if meta.path.is_ident("crate") {
+            krate = meta.value()?.parse()?;
+            Ok(())
+        } else {
+            Err(meta.error(
+                "unsupported register_block option, expected `flat`, `derive_debug`, `snapshot`, `must_use`, `group`, `no_panic`, `newtypes`, `max_offset`, `base`, `instances`, `stride`, `word_size`, `c_layout`, `exclusive`, `verify`, `lock` or `crate`",
+            ))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+    // the flat accessors are the only ones that can hand out a newtype
+    if let (Some(path), false) = (&newtypes, flat) {
+        return syn::Error::new_spanned(path, "`newtypes` requires `flat`")
+            .to_compile_error()
+            .into();
+    }
+    let instances = match (instances, instance_stride) {
+        (None, None) => None,
+        (Some((lit, 0)), Some(_)) => {
+            return syn::Error::new_spanned(lit, "`instances` must be at least 1")
+                .to_compile_error()
+                .into();
+        }
+        (Some((_, count)), Some((_, stride))) => Some((count, stride)),
+        (Some((lit, _)), None) | (None, Some((lit, _))) => {
+            return syn::Error::new_spanned(
+                lit,
+                "`instances` and `stride` must be given together, e.g. `instances = 3, stride = 0x100`",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    // `instance(i)` hands out blocks at other bases without any token
+    if let (Some(path), Some(_)) = (&exclusive, instances) {
+        return syn::Error::new_spanned(path, "`exclusive` can't be combined with `instances`")
+            .to_compile_error()
+            .into();
+    }
+
     // Parse the input struct
     let input = parse_macro_input!(item as ItemStruct);
     let struct_name = &input.ident;
@@ -28,135 +413,2152 @@ pub fn register_block(_attr: TokenStream, item: TokenStream) -> TokenStream {
         ).to_compile_error().into();
     }
 
-    #[allow(non_camel_case_types)]
-    #[derive(Clone, Copy)]
-    enum Access {
-        RW,
-        RO,
-        WO,
-        Clear,
-        RC,
-    }
-
-    use std::collections::HashMap;
-    let mut offset_map: HashMap<u32, Access> = HashMap::new();
+    // byte ranges claimed so far
+    let mut layout = layout::Layout::default();
+    layout.max_offset = max_offset;
     let mut struct_fields = Vec::new();
+    // `reset_<field>()` calls making up `reset_all()`, in field order
+    let mut reset_calls = Vec::new();
+    let mut has_reset = false;
+    // statements raising `size` to the end offset of every field, `BLOCK_SIZE` is the largest
+    let mut block_ends = Vec::new();
+    // `debug.field(..)` calls of the generated `Debug` impl
+    let mut debug_fields = Vec::new();
+    // fields of the `<Block>Snapshot` struct, and the reads filling them in `snapshot()`
+    let mut snapshot_fields = Vec::new();
+    let mut snapshot_reads = Vec::new();
+    // `Cell` fields holding the last value written to `shadow = true` registers, and their
+    // initializers in `new()`
+    let mut shadow_fields = Vec::new();
+    let mut shadow_inits = Vec::new();
+    // fields of the `#[register_block(c_layout)]` struct
+    let mut c_layout_entries = Vec::new();
+    // registers checked against the `verify` table
+    let mut verify_entries = Vec::new();
+    // (offset, table row) of every register, for the memory map in the struct docs
+    let mut memory_map = Vec::new();
+    // `RegisterInfo` entries of `REGISTERS`
+    let mut register_infos = Vec::new();
+    // names of the registers `registers_dyn()` leaves out, as their accesses aren't plain
+    let mut dyn_skipped = Vec::new();
+    // names of the `endian = "big"` registers, which `registers_dyn()` byte-swaps
+    let mut dyn_big_endian = Vec::new();
+    // statements of the generated `dump()`, copying registers into `out`
+    let mut dump_stmts = Vec::new();
+    // items emitted next to the impl block, e.g. deferred const assertions
+    let mut extra_items = Vec::new();
+    // `register = value` writes of the init sequence, checked once all registers are known
+    let init_writes = match init::parse(&input.attrs) {
+        Ok(writes) => writes,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    // `#[compose(...)]` reads assembled from several registers
+    let composes = match compose::parse(&input.attrs) {
+        Ok(composes) => composes,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    // `#[snapshot(...)]` reads of several registers as one value
+    let snapshots = match snapshot::parse(&input.attrs) {
+        Ok(snapshots) => snapshots,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    // registers that `#[init]`, `#[compose]`, `#[snapshot]` and `latch_via` may refer to
+    let mut register_targets = Vec::new();
+    // registers read after a trigger write, resolved once all registers are known
+    let mut latches = Vec::new();
+    // `#[cfg]`/`#[cfg_attr]` attributes of the field being processed, and where its items
+    // start in each output list
+    let mut pending_cfg: Option<(proc_macro2::TokenStream, [usize; 11])> = None;
+    // Gates the items of the previous field on its cfg attributes: its associated items move
+    // into an impl block of their own, everything else gets the attributes directly.
+    macro_rules! gate_pending_cfg {
+        () => {
+            if let Some((
+                cfg,
+                [fields, extra, resets, ends, debugs, dumps, infos, snapshots, snapshot_values, shadows, shadow_values],
+            )) = pending_cfg.take()
+            {
+                let items: Vec<_> = struct_fields.drain(fields..).collect();
+                for item in extra_items[extra..]
+                    .iter_mut()
+                    .chain(reset_calls[resets..].iter_mut())
+                    .chain(block_ends[ends..].iter_mut())
+                    .chain(debug_fields[debugs..].iter_mut())
+                    .chain(dump_stmts[dumps..].iter_mut())
+                    .chain(register_infos[infos..].iter_mut())
+                    .chain(snapshot_fields[snapshots..].iter_mut())
+                    .chain(snapshot_reads[snapshot_values..].iter_mut())
+                    .chain(shadow_fields[shadows..].iter_mut())
+                    .chain(shadow_inits[shadow_values..].iter_mut())
+                {
+                    *item = quote! { #cfg #item };
+                }
+                if !items.is_empty() {
+                    extra_items.push(quote! {
+                        #cfg
+                        impl<T: #krate::BaseAddress> #struct_name<T> {
+                            #(#items)*
+                        }
+                    });
+                }
+            }
+        };
+    }
+    // the number of bytes one unit of a register's `offset` or `stride` stands for
+    let unit = word_size.unwrap_or(1);
+    // Reserved ranges generate no accessors, but no register may live inside them
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("reserved"))
+    {
+        if word_size.is_some() {
+            return mixed_offsets(attr).into();
+        }
+        match layout.reserve(attr, None) {
+            Ok((start, end)) => {
+                block_ends.push(block_end(quote! { #end as usize }));
+                memory_map.push((
+                    start,
+                    format!("| 0x{:02X}..0x{:02X} | reserved | | | |", start, end),
+                    None,
+                ));
+            }
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+    // where a register without an explicit `offset` goes, or what keeps the macro from
+    // telling, for the error message
+    let mut cursor: Result<u32, String> = Ok(0);
     for field in fields {
-        let field_name = &field.ident;
+        gate_pending_cfg!();
+        let field_name = field.ident.as_ref().unwrap();
         let field_ty = &field.ty;
+        let cfg_attrs: Vec<_> = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg") || attr.path().is_ident("cfg_attr"))
+            .collect();
+        let cfg = quote! { #(#cfg_attrs)* };
+        // what the field's rows in the memory map and its overlap checks are gated on
+        let cfg_predicate = cfg_predicate(&cfg_attrs);
+        if c_layout && !cfg_attrs.is_empty() {
+            return syn::Error::new_spanned(
+                cfg_attrs[0],
+                "conditional registers are not supported with `c_layout`",
+            )
+            .to_compile_error()
+            .into();
+        }
+        if !cfg_attrs.is_empty() {
+            pending_cfg = Some((
+                cfg.clone(),
+                [
+                    struct_fields.len(),
+                    extra_items.len(),
+                    reset_calls.len(),
+                    block_ends.len(),
+                    debug_fields.len(),
+                    dump_stmts.len(),
+                    register_infos.len(),
+                    snapshot_fields.len(),
+                    snapshot_reads.len(),
+                    shadow_fields.len(),
+                    shadow_inits.len(),
+                ],
+            ));
+        }
+        if let Some(attr) = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("reserved"))
+        {
+            if word_size.is_some() {
+                return mixed_offsets(attr).into();
+            }
+            match layout.reserve(attr, cfg_predicate.clone()) {
+                Ok((start, end)) => {
+                    block_ends.push(block_end(quote! { #end as usize }));
+                    memory_map.push((
+                        start,
+                        format!("| 0x{:02X}..0x{:02X} | reserved | | | |", start, end),
+                        cfg_predicate,
+                    ));
+                    cursor = Ok(end);
+                }
+                Err(err) => return err.to_compile_error().into(),
+            }
+            continue;
+        }
+        // Windows are byte ranges such as a shared SRAM, accessed in slices
+        if let Some(attr) = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("window"))
+        {
+            if word_size.is_some() {
+                return mixed_offsets(attr).into();
+            }
+            let window = match window::Window::parse(attr) {
+                Ok(window) => window,
+                Err(err) => return err.to_compile_error().into(),
+            };
+            if let Err(message) = layout.claim(window.claim(field_name, cfg_predicate.clone())) {
+                return syn::Error::new_spanned(field_name, message)
+                    .to_compile_error()
+                    .into();
+            }
+            let doc_attrs: Vec<_> = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .collect();
+            let end = window.end();
+            memory_map.push((
+                window.offset,
+                format!(
+                    "| 0x{:02X}..0x{:02X} | `{}` | window | | {} |",
+                    window.offset,
+                    end,
+                    field_name,
+                    first_doc_line(&doc_attrs)
+                ),
+                cfg_predicate,
+            ));
+            struct_fields.push(window.accessors(&krate, no_panic, field_name, &doc_attrs));
+            block_ends.push(block_end(quote! { #end as usize }));
+            if c_layout {
+                let len = window.len as usize;
+                c_layout_entries.push(c_layout::Entry {
+                    name: field_name.clone(),
+                    ty: quote! { [u8; #len] },
+                    offset: window.offset,
+                    end,
+                    docs: doc_attrs,
+                });
+            }
+            cursor = Ok(end);
+            continue;
+        }
+        // Sub-blocks aren't registers themselves, they just hand out a child block
+        let sub_block = match field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("subblock"))
+        {
+            Some(attr) if word_size.is_some() => return mixed_offsets(attr).into(),
+            Some(attr) => match subblock::SubBlock::parse(attr, field_ty) {
+                Ok(sub_block) => Some(sub_block),
+                Err(err) => return err.to_compile_error().into(),
+            },
+            None if group
+                && !field
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("register")) =>
+            {
+                Some(subblock::SubBlock::group(field_ty))
+            }
+            None => None,
+        };
+        if let Some(sub_block) = sub_block {
+            if c_layout {
+                return syn::Error::new_spanned(
+                    field_name,
+                    "sub-blocks are not supported with `c_layout`",
+                )
+                .to_compile_error()
+                .into();
+            }
+            if let Some(max) = max_offset.filter(|max| sub_block.offset > *max) {
+                return syn::Error::new_spanned(
+                    field_name,
+                    format!(
+                        "sub-block `{}` at 0x{:X} lies past the max_offset of 0x{:X}",
+                        field_name, sub_block.offset, max
+                    ),
+                )
+                .to_compile_error()
+                .into();
+            }
+            // where the sub-block ends is only known once its own BLOCK_SIZE is
+            if let Some(max) = max_offset {
+                let end = sub_block.end();
+                let limit = max as usize + 1;
+                let message = format!(
+                    "sub-block `{}` extends past the max_offset of 0x{:X}",
+                    field_name, max
+                );
+                extra_items.push(quote_spanned! {field_name.span()=>
+                    const _: () = assert!(#end <= #limit, #message);
+                });
+            }
+            let doc_attrs: Vec<_> = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .collect();
+            let offset_const = format_ident!("{}_OFFSET", field_name.to_string().to_uppercase());
+            let offset = sub_block.offset as usize;
+            let doc = format!(
+                "Offset of the `{}` sub-block from the base address.",
+                field_name
+            );
+            struct_fields.push(quote! {
+                #[doc = #doc]
+                pub const #offset_const: usize = #offset;
+            });
+            dump_stmts.push(quote! {
+                self.#field_name().dump(&mut out[#offset..]);
+            });
+            // the blocks of a group share the parent's base on purpose
+            if !sub_block.shared {
+                layout.claim_sub_block(layout::SubBlockClaim {
+                    start: sub_block.offset,
+                    end: sub_block.end(),
+                    name: field_name.to_string(),
+                    cfg: cfg_predicate.clone(),
+                    span: field_name.span(),
+                });
+            }
+            let ty = &sub_block.ty;
+            memory_map.push((
+                sub_block.offset,
+                format!(
+                    "| 0x{:02X} | `{}` | sub-block | `{}` | {} |",
+                    offset,
+                    field_name,
+                    quote!(#ty),
+                    first_doc_line(&doc_attrs)
+                ),
+                cfg_predicate,
+            ));
+            struct_fields.push(sub_block.accessor(&krate, field_name, &doc_attrs));
+            block_ends.push(block_end(sub_block.end()));
+            cursor = Err(format!("the sub-block `{}`", field_name));
+            continue;
+        }
         let mut offset = None;
         let mut access = None;
+        let mut count = None;
+        let mut stride = None;
+        // the literals of `offset`, `count` and `stride`, for errors about the span they give
+        let mut span_lits: Vec<syn::LitInt> = Vec::new();
+        let mut width = None;
+        let mut reset: Option<syn::Expr> = None;
+        let mut clear_value: Option<syn::Expr> = None;
+        // the `unchecked` flag, kept for its span
+        let mut unchecked: Option<syn::Path> = None;
+        let mut big_endian = false;
+        // `word_order` of 128-bit registers, which are split into 32-bit accesses
+        let mut word_order: Option<syn::LitStr> = None;
+        let mut allow_any_type = false;
+        // `fifo = true`: a data port that steps through a queue on every access
+        let mut fifo: Option<syn::LitBool> = None;
+        // `shadow = true`: a WO register whose last written value is kept in the block
+        let mut shadow: Option<syn::LitBool> = None;
+        // `latch_via = "trigger"`: the register to write before reading this one
+        let mut latch_via: Option<syn::LitStr> = None;
+        let mut latch_value: Option<syn::Expr> = None;
+        // `cs = "Type"`: the `CriticalSection` guarding `modify_<field>_cs()`
+        let mut critical_section: Option<syn::LitStr> = None;
+        // keys of #[register(...)] that aren't options, reported together with missing ones
+        let mut unknown_keys = Vec::new();
         let mut doc_attrs = Vec::new();
+        let mut bit_fields = Vec::new();
         for attr in &field.attrs {
             if attr.path().is_ident("doc") {
                 doc_attrs.push(attr);
             }
+            if attr.path().is_ident("bits") || attr.path().is_ident("field") {
+                match bits::BitField::parse(attr) {
+                    Ok(bit_field) => bit_fields.push(bit_field),
+                    Err(err) => return err.to_compile_error().into(),
+                }
+            }
             if attr.path().is_ident("register") {
-                let _ = attr.parse_nested_meta(|meta| {
+                let parsed = attr.parse_nested_meta(|meta| {
                     if meta.path.is_ident("offset") {
-                        let lit: Lit = meta.value()?.parse()?;
-                        if let Lit::Int(litint) = lit {
-                            offset = Some(litint.base10_parse::<u32>().unwrap());
-                        }
+                        // base10_parse normalizes hex, octal, binary and `_`-separated literals
+                        let litint: syn::LitInt = meta.value()?.parse()?;
+                        offset = Some(scale(&litint, unit)?);
+                        span_lits.push(litint);
                     } else if meta.path.is_ident("access") {
-                        let lit: Lit = meta.value()?.parse()?;
-                        if let Lit::Str(litstr) = lit {
-                            let val = litstr.value().to_uppercase();
-                            match val.as_str() {
-                                "RW" => access = Some(Access::RW),
-                                "RO" => access = Some(Access::RO),
-                                "WO" => access = Some(Access::WO),
-                                "WC" => access = Some(Access::Clear),
-                                "RC" => access = Some(Access::RC),
-                                _ => panic!(
-                                    "Unknown access type: {}. Use RW, RO, WO, WC, or RC.",
-                                    val
+                        let litstr: syn::LitStr = meta.value()?.parse()?;
+                        access = Some(Access::parse(&litstr.value()).ok_or_else(|| {
+                            syn::Error::new_spanned(
+                                &litstr,
+                                format!(
+                                    "Unknown access type: {}. Use RW (read_write), RO (read_only), WO (write_only), WC (write_clear or clear), RC (read_clear), W1C or RW1C.",
+                                    litstr.value()
                                 ),
+                            )
+                        })?);
+                    } else if meta.path.is_ident("count") {
+                        let litint: syn::LitInt = meta.value()?.parse()?;
+                        count = Some(litint.base10_parse::<u32>()?);
+                        span_lits.push(litint);
+                    } else if meta.path.is_ident("stride") {
+                        let litint: syn::LitInt = meta.value()?.parse()?;
+                        stride = Some(scale(&litint, unit)?);
+                        span_lits.push(litint);
+                    } else if meta.path.is_ident("width") {
+                        let litint: syn::LitInt = meta.value()?.parse()?;
+                        width = Some(litint);
+                    } else if meta.path.is_ident("reset") {
+                        reset = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("clear_value") {
+                        clear_value = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("unchecked") {
+                        unchecked = Some(meta.path.clone());
+                    } else if meta.path.is_ident("allow_any_type") {
+                        allow_any_type = true;
+                    } else if meta.path.is_ident("fifo") {
+                        fifo = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("shadow") {
+                        shadow = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("latch_via") {
+                        latch_via = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("latch_value") {
+                        latch_value = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("cs") {
+                        critical_section = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("word_order") {
+                        word_order = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("endian") {
+                        let litstr: syn::LitStr = meta.value()?.parse()?;
+                        big_endian = match litstr.value().to_lowercase().as_str() {
+                            "big" => true,
+                            "native" => false,
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    &litstr,
+                                    format!(
+                                        "Unknown endianness: {}. Use \"big\" or \"native\".",
+                                        litstr.value()
+                                    ),
+                                ))
                             }
+                        };
+                    } else {
+                        unknown_keys.push(meta.path.clone());
+                        // skip the value so parsing can go on with the keys after it
+                        if meta.input.peek(syn::Token![=]) {
+                            meta.value()?.parse::<syn::Expr>()?;
                         }
                     }
                     Ok(())
                 });
+                if let Err(err) = parsed {
+                    return err.to_compile_error().into();
+                }
             }
         }
-        let offset =
-            offset.expect("Each register field must have #[register(offset = ..., access = ...)]");
-        let access =
-            access.expect("Each register field must have #[register(offset = ..., access = ...)]");
-        // Overlap check:
-        match access {
-            Access::RO => {
-                if let Some(existing) = offset_map.get(&offset) {
-                    match existing {
-                        Access::WO | Access::Clear => {} // allowed
-                        Access::RW | Access::RO | Access::RC => {
-                            return syn::Error::new_spanned(
+        let register_attr = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("register"));
+        let mut errors: Vec<syn::Error> = unknown_keys
+            .iter()
+            .map(|key| {
+                syn::Error::new_spanned(
+                    key,
+                    format!(
+                        "unknown key `{}` in #[register(...)], expected one of {}",
+                        quote!(#key),
+                        REGISTER_KEYS
+                    ),
+                )
+            })
+            .collect();
+        let message = match (register_attr, access) {
+            (Some(_), Some(_)) => None,
+            (None, _) => {
+                Some("Each register field must have #[register(offset = ..., access = ...)]")
+            }
+            (Some(_), None) => Some("missing `access` in #[register(offset = ..., access = ...)]"),
+        };
+        if let Some(message) = message {
+            errors.push(match register_attr {
+                Some(attr) => syn::Error::new_spanned(attr, message),
+                None => syn::Error::new_spanned(field_name, message),
+            });
+        }
+        let mut errors = errors.into_iter();
+        if let Some(mut err) = errors.next() {
+            err.extend(errors);
+            return err.to_compile_error().into();
+        }
+        let Some(access) = access else {
+            unreachable!("missing keys are reported above");
+        };
+        // Volatile accesses of arrays or structs are almost never valid MMIO operations
+        let primitive = is_unsigned(field_ty) || bits::unsigned_view(field_ty).is_some();
+        // Type aliases such as `type Reg = u32;` can't be resolved here, so with an explicit
+        // `width` the check that they name an integer (and of that size) happens at type-check
+        // time instead
+        let alias = !primitive
+            && matches!(field_ty, syn::Type::Path(path) if path.qself.is_none())
+            && width.is_some();
+        if !allow_any_type && !primitive && !alias {
+            let hint = if matches!(field_ty, syn::Type::Path(_)) {
+                " If it's an alias of an integer type, give its `width` in #[register(...)]."
+            } else {
+                ""
+            };
+            return syn::Error::new_spanned(
+                field_ty,
+                format!(
+                    "register `{}` has type `{}`, which is not a primitive integer.{} Add `allow_any_type` to #[register(...)] if accessing it as a whole is intended.",
+                    field_name,
+                    quote!(#field_ty),
+                    hint
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+        // byte-swapping is only defined for integers, see `Endian`
+        if big_endian && !primitive && !alias {
+            return syn::Error::new_spanned(
+                field_ty,
+                format!(
+                    "`endian = \"big\"` register `{}` has type `{}`, which is not a primitive integer",
+                    field_name,
+                    quote!(#field_ty)
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+        if alias && (!allow_any_type || big_endian) {
+            extra_items.push(quote_spanned! {syn::spanned::Spanned::span(field_ty)=>
+                const _: () = #krate::__private::assert_integer::<#field_ty>();
+            });
+        }
+        // An explicit bus width must match the size of the field type
+        let width = match width {
+            None => None,
+            Some(litint) => {
+                let bits = match litint.base10_parse::<u32>() {
+                    Ok(bits @ (8 | 16 | 32 | 64 | 128)) => bits,
+                    _ => {
+                        return syn::Error::new_spanned(
+                            &litint,
+                            "register width must be one of 8, 16, 32, 64 or 128",
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+                };
+                match primitive_width(field_ty) {
+                    Some(ty_bits) if ty_bits != bits => {
+                        return syn::Error::new_spanned(
+                            field_ty,
+                            format!(
+                                "register `{}` declares width = {} but its type `{}` is {} bits wide",
                                 field_name,
-                                format!("Duplicate register offset 0x{:X} for RO field {:?}. Only WO/Clear may overlap with RO.", offset, field_name)
-                            ).to_compile_error().into();
-                        }
+                                bits,
+                                quote!(#field_ty),
+                                ty_bits
+                            ),
+                        )
+                        .to_compile_error()
+                        .into();
                     }
+                    Some(_) => {}
+                    None => {
+                        let bytes = bits as usize / 8;
+                        let message = format!(
+                            "register `{}` declares width = {} but its type has a different size",
+                            field_name, bits
+                        );
+                        extra_items.push(quote! {
+                            const _: () = assert!(::core::mem::size_of::<#field_ty>() == #bytes, #message);
+                        });
+                    }
+                }
+                if bits == 64 {
+                    // proc macros can't emit warnings directly, so use a deprecated item to
+                    // surface the note on targets that can't do 64-bit bus accesses natively
+                    let note = format!(
+                        "register `{}` is 64 bits wide but this target has 32-bit pointers; a single 64-bit access may not be supported by the bus, consider splitting it into two 32-bit registers",
+                        field_name
+                    );
+                    extra_items.push(quote_spanned! {litint.span()=>
+                        #[cfg(target_pointer_width = "32")]
+                        const _: () = {
+                            #[deprecated(note = #note)]
+                            struct WideRegister;
+                            let _ = WideRegister;
+                        };
+                    });
                 }
+                Some(bits)
             }
-            Access::RW | Access::WO | Access::Clear | Access::RC => {
-                if let Some(existing) = offset_map.get(&offset) {
-                    match existing {
-                        Access::RO => {} // allowed
-                        _ => {
-                            return syn::Error::new_spanned(
-                                field_name,
-                                format!("Duplicate register offset 0x{:X} for RW/WO/Clear/RC field {:?}. Only RO may overlap with RW/WO/Clear/RC.", offset, field_name)
-                            ).to_compile_error().into();
-                        }
-                    }
+        };
+        // 128-bit registers are rarely a single bus transaction, so `width = 128` splits them
+        // into four 32-bit accesses, in the order given by `word_order`
+        let split = if width == Some(128) {
+            let order = match &word_order {
+                None => Ok(quote! { LowFirst }),
+                Some(litstr) => match litstr.value().as_str() {
+                    "low_first" => Ok(quote! { LowFirst }),
+                    "high_first" => Ok(quote! { HighFirst }),
+                    _ => Err(syn::Error::new_spanned(
+                        litstr,
+                        format!(
+                            "Unknown word order: {}. Use \"low_first\" or \"high_first\".",
+                            litstr.value()
+                        ),
+                    )),
+                },
+            };
+            let message = if !matches!(field_ty, syn::Type::Path(path) if path.path.is_ident("u128"))
+            {
+                Some("registers with width = 128 must be `u128`")
+            } else if !matches!(access, Access::RW | Access::RO | Access::WO) {
+                Some("registers with width = 128 must be RW, RO or WO")
+            } else if big_endian {
+                Some("registers with width = 128 can't be big-endian")
+            } else if unchecked.is_some() {
+                Some("registers with width = 128 can't be `unchecked`")
+            } else {
+                None
+            };
+            let order = match (order, message) {
+                (Ok(order), None) => order,
+                (Err(err), _) => return err.to_compile_error().into(),
+                (_, Some(message)) => {
+                    return syn::Error::new_spanned(field_name, message)
+                        .to_compile_error()
+                        .into()
+                }
+            };
+            Some(order)
+        } else {
+            if let Some(litstr) = &word_order {
+                return syn::Error::new_spanned(litstr, "`word_order` requires width = 128")
+                    .to_compile_error()
+                    .into();
+            }
+            None
+        };
+        // Register arrays repeat the register `count` times, `stride` bytes apart
+        let array = match (count, stride) {
+            (None, None) => None,
+            (Some(0), _) => {
+                return syn::Error::new_spanned(
+                    field_name,
+                    "register arrays need a `count` of at least 1",
+                )
+                .to_compile_error()
+                .into();
+            }
+            (Some(count), stride) => {
+                let Some(stride) =
+                    stride.or(width.or(primitive_width(field_ty)).map(|width| width / 8))
+                else {
+                    return syn::Error::new_spanned(
+                        field_name,
+                        "register arrays of this type need an explicit `stride`",
+                    )
+                    .to_compile_error()
+                    .into();
+                };
+                Some((count, stride))
+            }
+            (None, Some(_)) => {
+                return syn::Error::new_spanned(field_name, "`stride` requires `count`")
+                    .to_compile_error()
+                    .into();
+            }
+        };
+        // every access of a FIFO port goes to the same address, so it can't be an array
+        let fifo = match fifo {
+            Some(lit) if lit.value && array.is_some() => {
+                return syn::Error::new_spanned(lit, "`fifo` registers can't have a `count`")
+                    .to_compile_error()
+                    .into();
+            }
+            Some(lit) => lit.value,
+            None => false,
+        };
+        let shadow = match shadow {
+            Some(lit) if lit.value => {
+                let message = if !matches!(access, Access::WO) {
+                    Some("`shadow` is only supported on WO registers")
+                } else if array.is_some() {
+                    Some("`shadow` registers can't have a `count`")
+                } else if unchecked.is_some() {
+                    Some("`shadow` registers can't be `unchecked`")
+                } else if !(is_unsigned(field_ty) || primitive_width(field_ty).is_some() || alias) {
+                    Some("`shadow` registers must have an integer type")
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    return syn::Error::new_spanned(lit, message)
+                        .to_compile_error()
+                        .into();
                 }
+                true
             }
+            _ => false,
+        };
+        // Overlap check: a read-side field may cover exactly the same bytes as a write-side
+        // field (e.g. RO over WO), but never any bytes of another field on the same side.
+        // Registers claim `[offset, offset + size)`, arrays their whole span. Types the macro
+        // can't size without a `width` are assumed to take a single byte.
+        let size = width
+            .or(primitive_width(field_ty))
+            .map_or(1, |width| width / 8);
+        // Without an explicit `offset` the register follows the previous one, without padding
+        let offset = match (offset, &cursor) {
+            (Some(offset), _) => offset,
+            (None, Ok(cursor)) if cursor % size != 0 => {
+                return syn::Error::new_spanned(
+                    field_name,
+                    format!(
+                        "register `{}` would be placed at 0x{:X}, which isn't aligned to its size; give it an explicit `offset`",
+                        field_name, cursor
+                    ),
+                )
+                .to_compile_error()
+                .into();
+            }
+            (None, Ok(cursor)) => *cursor,
+            (None, Err(previous)) => {
+                return syn::Error::new_spanned(
+                    field_name,
+                    format!(
+                        "register `{}` has no `offset` and can't be placed after {}; give it an explicit `offset`",
+                        field_name, previous
+                    ),
+                )
+                .to_compile_error()
+                .into();
+            }
+        };
+        let end = match array {
+            Some((count, stride)) => count.checked_mul(stride).and_then(|span| {
+                let last = (count - 1) * stride;
+                Some(
+                    offset
+                        .checked_add(span)?
+                        .max(offset.checked_add(last)?.checked_add(size)?),
+                )
+            }),
+            None => offset.checked_add(size),
+        };
+        let Some(end) = end else {
+            // point at the last of `offset`, `count` and `stride` given
+            let message = format!(
+                "register `{}` reaches past the end of the 32-bit address range",
+                field_name
+            );
+            let error = match span_lits.last() {
+                Some(lit) => syn::Error::new_spanned(lit, message),
+                None => syn::Error::new_spanned(field_name, message),
+            };
+            return error.to_compile_error().into();
+        };
+        let claim = layout::Claim {
+            start: offset,
+            end,
+            reads: access.read_side(),
+            writes: access.writes(),
+            name: field_name.to_string(),
+            reserved: false,
+            cfg: cfg_predicate.clone(),
+            span: field_name.span(),
+        };
+        if let Err(message) = layout.claim(claim) {
+            return syn::Error::new_spanned(field_name, message)
+                .to_compile_error()
+                .into();
+        }
+        cursor = match width.or(primitive_width(field_ty)) {
+            Some(_) => Ok(end),
+            None => Err(format!("`{}`, whose size the macro can't tell", field_name)),
+        };
+        // `#[repr(C)]` pads fields to their alignment, so only naturally aligned registers
+        // (and arrays of them without gaps) are expressible in plain fields
+        if c_layout {
+            let message = match (width.or(primitive_width(field_ty)), array) {
+                (None, _) => Some(format!(
+                    "`c_layout` needs the size of `{}`; give it a `width`",
+                    field_name
+                )),
+                (Some(_), _) if offset % size != 0 => Some(format!(
+                    "`{}` at 0x{:X} isn't aligned to its size, which `c_layout` can't express",
+                    field_name, offset
+                )),
+                (Some(_), Some((_, stride))) if stride != size => Some(format!(
+                    "the elements of `{}` have gaps between them, which `c_layout` can't express",
+                    field_name
+                )),
+                _ => None,
+            };
+            if let Some(message) = message {
+                return syn::Error::new_spanned(field_name, message)
+                    .to_compile_error()
+                    .into();
+            }
+            c_layout_entries.push(c_layout::Entry {
+                name: field_name.clone(),
+                ty: match array {
+                    None => quote! { #field_ty },
+                    Some((count, _)) => {
+                        let count = count as usize;
+                        quote! { [#field_ty; #count] }
+                    }
+                },
+                offset,
+                end,
+                docs: doc_attrs.clone(),
+            });
         }
-        offset_map.insert(offset, access.clone());
+        let offset_const = format_ident!("{}_OFFSET", field_name.to_string().to_uppercase());
+        verify_entries.push(verify::Entry {
+            cfg: cfg.clone(),
+            name: field_name.clone(),
+            offset_const: offset_const.clone(),
+            ty: field_ty.clone(),
+            array,
+        });
+        let doc = format!("Offset of `{}` from the base address.", field_name);
+        struct_fields.push(quote! {
+            #[doc = #doc]
+            pub const #offset_const: usize = #offset as usize;
+        });
+        block_ends.push(block_end(match array {
+            None => quote! { #offset as usize + ::core::mem::size_of::<#field_ty>() },
+            Some((count, stride)) => quote! {
+                #offset as usize + (#count as usize - 1) * #stride as usize + ::core::mem::size_of::<#field_ty>()
+            },
+        }));
         // Generate accessor function based on access type
-        let (ptr_type, init_expr) = match access {
-            Access::RW => (
-                quote! { ::register_block::RW<#field_ty> },
-                quote! { unsafe { ::register_block::RW::new(self.base.base_address() + #offset as usize) } },
-            ),
-            Access::RO => (
-                quote! { ::register_block::RO<#field_ty> },
-                quote! { unsafe { ::register_block::RO::new(self.base.base_address() + #offset as usize) } },
-            ),
-            Access::WO => (
-                quote! { ::register_block::WO<#field_ty> },
-                quote! { unsafe { ::register_block::WO::new(self.base.base_address() + #offset as usize) } },
-            ),
-            Access::Clear => (
-                quote! { ::register_block::WC<#field_ty> },
-                quote! { unsafe { ::register_block::WC::new(self.base.base_address() + #offset as usize) } },
-            ),
-            Access::RC => (
-                quote! { ::register_block::RC<#field_ty> },
-                quote! { unsafe { ::register_block::RC::new(self.base.base_address() + #offset as usize) } },
+        let mut wrapper = match access {
+            Access::RW => quote! { RW },
+            Access::RO => quote! { RO },
+            Access::WO => quote! { WO },
+            Access::WC => quote! { WC },
+            Access::RC => quote! { RC },
+            Access::W1C | Access::RW1C => quote! { W1C },
+        };
+        // the returned wrapper borrows the block, so it can't outlive it
+        let mut ptr_type = quote! { #krate::#wrapper<'_, #field_ty> };
+        if big_endian {
+            ptr_type = quote! { #krate::BE<#ptr_type> };
+        }
+        // WC registers may write something other than `Default::default()` to clear
+        let constructor = match (&clear_value, access) {
+            (None, _) => quote! { new },
+            (Some(_), Access::WC) => quote! { with_clear_value },
+            (Some(value), _) => {
+                return syn::Error::new_spanned(
+                    value,
+                    "`clear_value` is only supported on WC registers",
+                )
+                .to_compile_error()
+                .into();
+            }
+        };
+        // unchecked registers only get the plain accessor, returning an `Unaligned*` wrapper
+        // whose `unsafe` `_unchecked` methods are the caller's responsibility
+        let constructor = match &unchecked {
+            None => constructor,
+            Some(path) => {
+                let message = if flat {
+                    Some("`unchecked` registers are not supported in flat mode")
+                } else if !matches!(access, Access::RW | Access::RO | Access::WO) {
+                    Some("`unchecked` is only supported on RW, RO and WO registers")
+                } else if !bit_fields.is_empty() {
+                    Some("`unchecked` registers can't have #[bits] or #[field]")
+                } else if big_endian {
+                    Some("`unchecked` registers can't be big-endian")
+                } else if critical_section.is_some() {
+                    Some("`unchecked` registers can't have `cs`")
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    return syn::Error::new_spanned(path, message)
+                        .to_compile_error()
+                        .into();
+                }
+                let unaligned = format_ident!("Unaligned{}", wrapper.to_string());
+                ptr_type = quote! { #krate::#unaligned<'_, #field_ty> };
+                wrapper = quote! { #unaligned };
+                quote! { new }
+            }
+        };
+        let clear_arg = clear_value.as_ref().map(|value| quote! { , #value });
+        // array accessors take the element index as their first parameter
+        let base = quote! { self.base.base_address() };
+        let (index_param, address) = match array {
+            None => (
+                quote! {},
+                offset_address(&krate, no_panic, base, quote! { #offset as usize }),
             ),
+            Some((count, stride)) => {
+                let message = format!(
+                    "index {{}} out of range for register array `{}` of length {}",
+                    field_name, count
+                );
+                // `no_panic` blocks leave the index unchecked, as release builds do anyway
+                let check = (!no_panic).then(|| {
+                    quote! { debug_assert!(index < #count as usize, #message, index); }
+                });
+                let element_offset = if no_panic {
+                    quote! { (#offset as usize).wrapping_add(index.wrapping_mul(#stride as usize)) }
+                } else {
+                    quote! { #offset as usize + index * #stride as usize }
+                };
+                let address = offset_address(&krate, no_panic, base, element_offset);
+                (
+                    quote! { index: usize, },
+                    quote! {{
+                        #check
+                        #address
+                    }},
+                )
+            }
         };
-        let accessor = quote! {
-            #(#doc_attrs)*
+        let mut init_expr = quote! {
+            unsafe { #krate::#wrapper::#constructor(#address #clear_arg) }
+        };
+        // split registers are accessed a word at a time, so they only need a word's alignment
+        if let Some(order) = &split {
+            ptr_type = quote! { #krate::Split<#ptr_type> };
+            init_expr = quote! {
+                unsafe {
+                    #krate::Split::<#krate::#wrapper<#field_ty>>::from_address(
+                        #address,
+                        #krate::WordOrder::#order,
+                    )
+                }
+            };
+        }
+        // dumps copy the bytes as they are on the bus, so skip the byte swapping
+        if unchecked.is_none()
+            && matches!(access, Access::RW | Access::RO | Access::W1C | Access::RW1C)
+        {
+            let raw_type = match split {
+                Some(_) => ptr_type.clone(),
+                None => quote! { #krate::#wrapper<'_, #field_ty> },
+            };
+            let copy = quote! {
+                let reg: #raw_type = #init_expr;
+                #krate::__private::copy_bytes(out, offset, reg.read());
+            };
+            dump_stmts.push(match array {
+                None => quote! {{
+                    let offset = #offset as usize;
+                    #copy
+                }},
+                Some((count, stride)) => quote! {
+                    for index in 0..#count as usize {
+                        let offset = #offset as usize + index * #stride as usize;
+                        #copy
+                    }
+                },
+            });
+        }
+        if big_endian {
+            init_expr = quote! { #krate::BE::new(#init_expr) };
+        }
+        let ptr_fn = format_ident!("ptr_{}", field_name);
+        let raw_ptr = match access {
+            Access::RO => quote! { *const #field_ty },
+            _ => quote! { *mut #field_ty },
+        };
+        let doc = format!(
+            "Raw pointer to `{}`, e.g. to hand to a DMA engine.",
+            field_name
+        );
+        struct_fields.push(quote! {
+            #[doc = #doc]
             #[inline(always)]
-            pub fn #field_name(&self) -> #ptr_type {
-                #init_expr
+            pub fn #ptr_fn(&self, #index_param) -> #raw_ptr {
+                let address = #address;
+                address as #raw_ptr
+            }
+        });
+        // with a constant base the address is known at compile time, so it can be computed in
+        // const contexts
+        let addr_fn = format_ident!("addr_{}", field_name);
+        let doc = format!(
+            "Address of `{}`, usable in const contexts, e.g. to build tables of register addresses.",
+            field_name
+        );
+        let const_address = match array {
+            None => quote! { BASE + #offset as usize },
+            Some((count, stride)) => {
+                let message = format!(
+                    "index out of range for register array `{}` of length {}",
+                    field_name, count
+                );
+                let check = (!no_panic).then(|| {
+                    quote! { debug_assert!(index < #count as usize, #message); }
+                });
+                quote! {
+                    #check
+                    BASE + #offset as usize + index * #stride as usize
+                }
             }
         };
-        struct_fields.push(accessor);
+        extra_items.push(quote! {
+            impl<const BASE: usize> #struct_name<#krate::ConstantAddress<BASE>> {
+                #[doc = #doc]
+                pub const fn #addr_fn(&self, #index_param) -> usize {
+                    #const_address
+                }
+            }
+        });
+        if fifo && unchecked.is_none() {
+            if access.reads() {
+                let drain_fn = format_ident!("drain_{}", field_name);
+                let doc = format!(
+                    "Reads `{}` `out.len()` times, filling `out` with the next entries of the FIFO.",
+                    field_name
+                );
+                struct_fields.push(quote! {
+                    #[doc = #doc]
+                    pub fn #drain_fn(&self, out: &mut [#field_ty]) {
+                        let reg: #ptr_type = #init_expr;
+                        for slot in out {
+                            *slot = reg.read();
+                        }
+                    }
+                });
+            }
+            if matches!(access, Access::RW | Access::WO) {
+                let fill_fn = format_ident!("fill_{}", field_name);
+                let doc = format!(
+                    "Writes `values` to `{}` one after another, pushing them into the FIFO in order.",
+                    field_name
+                );
+                struct_fields.push(quote! {
+                    #[doc = #doc]
+                    pub fn #fill_fn(&self, values: &[#field_ty]) {
+                        let reg: #ptr_type = #init_expr;
+                        for value in values {
+                            reg.write(*value);
+                        }
+                    }
+                });
+            }
+        }
+        if let Some((count, _)) = array {
+            let len_fn = format_ident!("{}_len", field_name);
+            let doc = format!("Number of registers in the `{}` array.", field_name);
+            struct_fields.push(quote! {
+                #[doc = #doc]
+                #[inline(always)]
+                pub const fn #len_fn(&self) -> usize {
+                    #count as usize
+                }
+            });
+            if access.reads() && unchecked.is_none() {
+                let into_fn = format_ident!("read_{}_into", field_name);
+                let message = format!(
+                    "{{}} values don't fit the {} registers of `{}`",
+                    count, field_name
+                );
+                let check = (!no_panic).then(|| {
+                    quote! { debug_assert!(dst.len() <= #count as usize, #message, dst.len()); }
+                });
+                let doc = format!(
+                    "Reads `dst.len()` consecutive `{}` registers from index 0 into `dst`, e.g. to \
+                     drain a FIFO window. Entries past the end of the array are left alone.",
+                    field_name
+                );
+                struct_fields.push(quote! {
+                    #[doc = #doc]
+                    pub fn #into_fn(&self, dst: &mut [#field_ty]) {
+                        #check
+                        for (index, slot) in dst.iter_mut().take(#count as usize).enumerate() {
+                            let reg: #ptr_type = #init_expr;
+                            *slot = reg.read();
+                        }
+                    }
+                });
+            }
+        }
+        // reading RC registers (or anything not readable) would have side effects
+        if derive_debug
+            && unchecked.is_none()
+            && matches!(access, Access::RW | Access::RO | Access::W1C | Access::RW1C)
+        {
+            let name = field_name.to_string();
+            let read = quote! {{
+                let reg: #ptr_type = #init_expr;
+                reg.read()
+            }};
+            debug_fields.push(match array {
+                None => quote! {
+                    debug.field(#name, &#krate::__private::Hex(#read));
+                },
+                Some((count, _)) => quote! {
+                    debug.field(#name, &#krate::__private::HexList(#count as usize, |index: usize| #read));
+                },
+            });
+        }
+        if snapshot
+            && unchecked.is_none()
+            && matches!(access, Access::RW | Access::RO | Access::W1C | Access::RW1C)
+        {
+            let read = quote! {{
+                let reg: #ptr_type = #init_expr;
+                reg.read()
+            }};
+            match array {
+                None => {
+                    snapshot_fields.push(quote! { #(#doc_attrs)* pub #field_name: #field_ty, });
+                    snapshot_reads.push(quote! { #field_name: #read, });
+                }
+                Some((count, _)) => {
+                    snapshot_fields.push(quote! {
+                        #(#doc_attrs)* pub #field_name: [#field_ty; #count as usize],
+                    });
+                    snapshot_reads.push(quote! {
+                        #field_name: ::core::array::from_fn(|index: usize| #read),
+                    });
+                }
+            }
+        }
+        if let Some(reset) = &reset {
+            has_reset = true;
+            let reset_const = format_ident!("RESET_{}", field_name.to_string().to_uppercase());
+            let doc = format!("Reset value of `{}`.", field_name);
+            struct_fields.push(quote! {
+                #[doc = #doc]
+                pub const #reset_const: #field_ty = #reset;
+            });
+            // only plain writable registers can be driven back to their reset value
+            if unchecked.is_none() && matches!(access, Access::RW | Access::WO) {
+                let reset_fn = format_ident!("reset_{}", field_name);
+                let doc = format!("Writes the reset value to `{}`.", field_name);
+                let write_reset = quote! {
+                    let reg: #ptr_type = #init_expr;
+                    reg.write(Self::#reset_const);
+                };
+                let body = match array {
+                    None => write_reset,
+                    Some((count, _)) => quote! {
+                        for index in 0..#count as usize {
+                            #write_reset
+                        }
+                    },
+                };
+                struct_fields.push(quote! {
+                    #[doc = #doc]
+                    #[inline(always)]
+                    pub fn #reset_fn(&self) {
+                        #body
+                    }
+                });
+                reset_calls.push(quote! { self.#reset_fn(); });
+            }
+        }
+        if let Some(first) = bit_fields.first() {
+            if !matches!(access, Access::RW | Access::RO) {
+                return syn::Error::new(
+                    first.span,
+                    "#[bits] and #[field] are only supported on RW and RO registers",
+                )
+                .to_compile_error()
+                .into();
+            }
+        }
+        if let Err(err) = bits::check_layout(&bit_fields, width.or(primitive_width(field_ty))) {
+            return err.to_compile_error().into();
+        }
+        for bit_field in &bit_fields {
+            let getter =
+                bit_field.getter(field_name, field_ty, &index_param, &ptr_type, &init_expr);
+            let must_use = must_use.then(|| quote! { #[must_use] });
+            struct_fields.push(quote! { #must_use #getter });
+            if access.writes() {
+                struct_fields.push(bit_field.setter(
+                    field_name,
+                    field_ty,
+                    &index_param,
+                    &ptr_type,
+                    &init_expr,
+                    no_panic,
+                ));
+                struct_fields.extend(bit_field.checked_setters(&krate, &index_param));
+            }
+            if primitive_width(field_ty).is_none() {
+                extra_items.push(bit_field.width_assertion(field_name, field_ty));
+            }
+            extra_items.extend(bit_field.values_enum(field_name, &input.vis));
+        }
+        let location = match array {
+            None => format!("0x{:02X}", offset),
+            Some((count, stride)) => format!("0x{:02X} + 0x{:X} × 0..{}", offset, stride, count),
+        };
+        memory_map.push((
+            offset,
+            format!(
+                "| {} | `{}` | {:?} | `{}` | {} |",
+                location,
+                field_name,
+                access,
+                quote!(#field_ty),
+                first_doc_line(&doc_attrs)
+            ),
+            cfg_predicate.clone(),
+        ));
+        let name = field_name.to_string();
+        let access_name = format_ident!("{}", format!("{:?}", access));
+        let (count, stride) = match array {
+            None => (quote! { 1 }, quote! { ::core::mem::size_of::<#field_ty>() }),
+            Some((count, stride)) => (quote! { #count as usize }, quote! { #stride as usize }),
+        };
+        let integer = is_unsigned(field_ty) || primitive_width(field_ty).is_some() || alias;
+        let reset_info = match &reset {
+            Some(_) if integer => {
+                let reset_const = format_ident!("RESET_{}", field_name.to_string().to_uppercase());
+                let stored = if big_endian {
+                    quote! { Self::#reset_const.to_be() }
+                } else {
+                    quote! { Self::#reset_const }
+                };
+                quote! { ::core::option::Option::Some(#stored as u128) }
+            }
+            _ => quote! { ::core::option::Option::None },
+        };
+        if unchecked.is_some() || allow_any_type {
+            dyn_skipped.push(name.clone());
+        }
+        if big_endian {
+            dyn_big_endian.push(name.clone());
+        }
+        register_infos.push(quote! {
+            #krate::RegisterInfo {
+                name: #name,
+                offset: #offset as usize,
+                width: (::core::mem::size_of::<#field_ty>() * 8) as u32,
+                access: #krate::Access::#access_name,
+                count: #count,
+                stride: #stride,
+                reset: #reset_info,
+            }
+        });
+        let unsupported = if array.is_some() {
+            Some("register arrays are not supported")
+        } else if unchecked.is_some() {
+            Some("`unchecked` registers are not supported")
+        } else {
+            None
+        };
+        let cs_ty = match critical_section {
+            None => None,
+            Some(lit) if !matches!(access, Access::RW) => {
+                return syn::Error::new_spanned(lit, "`cs` is only supported on RW registers")
+                    .to_compile_error()
+                    .into();
+            }
+            Some(lit) => match lit.parse::<syn::Type>() {
+                Ok(ty) => Some(ty),
+                Err(err) => return err.to_compile_error().into(),
+            },
+        };
+        // `modify_<field>_cs()`, taking and returning `value_ty`
+        let modify_cs = |value_ty: &proc_macro2::TokenStream| {
+            cs_ty.as_ref().map(|cs_ty| {
+                let modify_cs_fn = format_ident!("modify_{}_cs", field_name);
+                let doc = format!(
+                    "Read-modify-write of `{}` inside the critical section `{}`, for registers shared with interrupt handlers.",
+                    field_name,
+                    quote!(#cs_ty)
+                );
+                quote! {
+                    #[doc = #doc]
+                    pub fn #modify_cs_fn<F>(&self, #index_param f: F)
+                    where
+                        F: FnOnce(#value_ty) -> #value_ty,
+                    {
+                        <#cs_ty as #krate::CriticalSection>::with(|| {
+                            let reg: #ptr_type = #init_expr;
+                            reg.modify(|raw| <#field_ty>::from(f(<#value_ty>::from(raw))))
+                        })
+                    }
+                }
+            })
+        };
+        // the shadow starts out at the reset value, as the register does
+        let shadow_field = format_ident!("shadow_{}", field_name);
+        if shadow {
+            let initial = match &reset {
+                Some(reset) => quote! { #reset },
+                None => quote! { 0 },
+            };
+            shadow_fields.push(quote! {
+                #shadow_field: ::core::cell::Cell<#field_ty>,
+            });
+            shadow_inits.push(quote! {
+                #shadow_field: ::core::cell::Cell::new(#initial),
+            });
+        }
+        // `write_<field>()`, `modify_<field>()` and `shadow_<field>()` of a shadowed register,
+        // taking and returning `value_ty`
+        let shadow_fns = |value_ty: &proc_macro2::TokenStream| {
+            let write_fn = format_ident!("write_{}", field_name);
+            let modify_fn = format_ident!("modify_{}", field_name);
+            let write_doc = format!(
+                "Writes `{}` and remembers the value in the shadow.",
+                field_name
+            );
+            let modify_doc = format!(
+                "Read-modify-write of `{}` through its shadow, as the register itself can't be read. The shadow belongs to this block alone, so other blocks at the same base must not write the register.",
+                field_name
+            );
+            let shadow_doc = format!("The value last written to `{}`.", field_name);
+            quote! {
+                #[doc = #write_doc]
+                #[inline(always)]
+                pub fn #write_fn(&self, value: #value_ty) {
+                    let raw = <#field_ty>::from(value);
+                    let reg: #ptr_type = #init_expr;
+                    reg.write(raw);
+                    self.#shadow_field.set(raw);
+                }
+                #[doc = #modify_doc]
+                #[inline(always)]
+                pub fn #modify_fn<F>(&self, f: F)
+                where
+                    F: FnOnce(#value_ty) -> #value_ty,
+                {
+                    self.#write_fn(f(<#value_ty>::from(self.#shadow_field.get())))
+                }
+                #[doc = #shadow_doc]
+                #[inline(always)]
+                pub fn #shadow_field(&self) -> #value_ty {
+                    <#value_ty>::from(self.#shadow_field.get())
+                }
+            }
+        };
+        match (latch_via, latch_value) {
+            (Some(trigger), value) => {
+                let message = if !access.reads() {
+                    Some("`latch_via` registers must be readable")
+                } else if array.is_some() {
+                    Some("`latch_via` registers can't have a `count`")
+                } else if unchecked.is_some() {
+                    Some("`latch_via` registers can't be `unchecked`")
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    return syn::Error::new_spanned(&trigger, message)
+                        .to_compile_error()
+                        .into();
+                }
+                latches.push(latch::Latch {
+                    register: field_name.clone(),
+                    trigger,
+                    value,
+                    cfg: cfg.clone(),
+                    ty: field_ty.clone(),
+                    ptr_type: ptr_type.clone(),
+                    init_expr: init_expr.clone(),
+                });
+            }
+            (None, Some(value)) => {
+                return syn::Error::new_spanned(value, "`latch_value` requires `latch_via`")
+                    .to_compile_error()
+                    .into();
+            }
+            (None, None) => {}
+        }
+        register_targets.push(init::Target {
+            name: field_name.clone(),
+            cfg: cfg.clone(),
+            ty: field_ty.clone(),
+            offset,
+            bytes: primitive_width(field_ty)
+                .filter(|bits| width.is_none_or(|width| width == *bits))
+                .filter(|_| {
+                    array.is_none()
+                        && unchecked.is_none()
+                        && !big_endian
+                        && split.is_none()
+                        && cfg_attrs.is_empty()
+                })
+                .map(|bits| bits / 8),
+            unwritable: if !matches!(access, Access::RW | Access::WO) {
+                Some("only RW and WO registers can be written")
+            } else {
+                unsupported
+            },
+            unreadable: if !matches!(access, Access::RW | Access::RO) {
+                Some("only RW and RO registers can be read")
+            } else {
+                unsupported
+            },
+            ptr_type: ptr_type.clone(),
+            init_expr: init_expr.clone(),
+        });
+        if !flat {
+            let accessor = quote! {
+                #(#doc_attrs)*
+                #[inline(always)]
+                pub fn #field_name(&self, #index_param) -> #ptr_type {
+                    #init_expr
+                }
+            };
+            struct_fields.push(accessor);
+            // a proxy standing in for `Index`, which would have to hand out a reference
+            if let Some((count, _)) = array {
+                let array_ty = format_ident!("{}Array", camel_case(&field_name.to_string()));
+                let array_fn = format_ident!("{}_array", field_name);
+                let vis = &input.vis;
+                let doc = format!(
+                    "The elements of the `{}` register array of [`{}`], see `{}()`.",
+                    field_name, struct_name, array_fn
+                );
+                let fn_doc = format!(
+                    "The `{}` register array, whose `at(index)` returns the same element as `{}(index)`.",
+                    field_name, field_name
+                );
+                struct_fields.push(quote! {
+                    #[doc = #fn_doc]
+                    #[inline(always)]
+                    pub fn #array_fn(&self) -> #array_ty<'_, T> {
+                        #array_ty { block: self }
+                    }
+                });
+                extra_items.push(quote! {
+                    #[doc = #doc]
+                    #[derive(Clone, Copy)]
+                    #vis struct #array_ty<'a, T: #krate::BaseAddress> {
+                        block: &'a #struct_name<T>,
+                    }
+                    impl<T: #krate::BaseAddress> #array_ty<'_, T> {
+                        /// Number of elements.
+                        pub const LEN: usize = #count as usize;
+                        /// Number of elements.
+                        pub const fn len(&self) -> usize {
+                            Self::LEN
+                        }
+                        /// Whether the array has no elements, which it never does.
+                        pub const fn is_empty(&self) -> bool {
+                            Self::LEN == 0
+                        }
+                        /// Element `index`. Out-of-range indices are caught by a debug assertion.
+                        #[inline(always)]
+                        pub fn at(&self, index: usize) -> #ptr_type {
+                            self.block.#field_name(index)
+                        }
+                        /// Element `index`, or `None` if it's out of range.
+                        #[inline(always)]
+                        pub fn get(&self, index: usize) -> ::core::option::Option<#ptr_type> {
+                            (index < Self::LEN).then(|| self.block.#field_name(index))
+                        }
+                        /// Every element, in order.
+                        pub fn iter(&self) -> impl ::core::iter::Iterator<Item = #ptr_type> + '_ {
+                            (0..Self::LEN).map(|index| self.block.#field_name(index))
+                        }
+                    }
+                });
+            }
+            struct_fields.extend(modify_cs(&quote! { #field_ty }));
+            if shadow {
+                struct_fields.push(shadow_fns(&quote! { #field_ty }));
+            }
+            continue;
+        }
+        let read_fn = format_ident!("read_{}", field_name);
+        let write_fn = format_ident!("write_{}", field_name);
+        let clear_fn = format_ident!("clear_{}", field_name);
+        let modify_fn = format_ident!("modify_{}", field_name);
+        let write_masked_fn = format_ident!("write_{}_masked", field_name);
+        let write_verify_fn = format_ident!("write_{}_verify", field_name);
+        let try_write_verify_fn = format_ident!("try_write_{}_verify", field_name);
+        let ack_fn = format_ident!("ack_{}", field_name);
+        let clear_bits_fn = format_ident!("clear_bits_{}", field_name);
+        let poll_set_fn = format_ident!("poll_{}_set", field_name);
+        let poll_clear_fn = format_ident!("poll_{}_clear", field_name);
+        let poll_set_timeout_fn = format_ident!("poll_{}_set_timeout", field_name);
+        let poll_clear_timeout_fn = format_ident!("poll_{}_clear_timeout", field_name);
+        // values go in and out through `From`, which is the identity without `newtypes`
+        let value_ty = match newtypes {
+            None => quote! { #field_ty },
+            Some(_) => {
+                let value_ty = format_ident!("{}Value", camel_case(&field_name.to_string()));
+                let vis = &input.vis;
+                let doc = format!(
+                    "A value of the `{}` register of [`{}`].",
+                    field_name, struct_name
+                );
+                extra_items.push(quote! {
+                    #[doc = #doc]
+                    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                    #vis struct #value_ty(pub #field_ty);
+                    impl ::core::convert::From<#field_ty> for #value_ty {
+                        fn from(raw: #field_ty) -> Self {
+                            Self(raw)
+                        }
+                    }
+                    impl ::core::convert::From<#value_ty> for #field_ty {
+                        fn from(value: #value_ty) -> Self {
+                            value.0
+                        }
+                    }
+                    impl ::core::ops::Deref for #value_ty {
+                        type Target = #field_ty;
+                        fn deref(&self) -> &#field_ty {
+                            &self.0
+                        }
+                    }
+                });
+                quote! { #value_ty }
+            }
+        };
+        if access.reads() {
+            let must_use = if matches!(access, Access::RC) {
+                Some(quote! {
+                    #[must_use = "reading clears the register, so the value is lost if it's not used"]
+                })
+            } else {
+                must_use.then(|| quote! { #[must_use] })
+            };
+            struct_fields.push(quote! {
+                #(#doc_attrs)*
+                #must_use
+                #[inline(always)]
+                pub fn #read_fn(&self, #index_param) -> #value_ty {
+                    let reg: #ptr_type = #init_expr;
+                    <#value_ty>::from(reg.read())
+                }
+            });
+        }
+        struct_fields.extend(modify_cs(&value_ty));
+        match access {
+            Access::RW => struct_fields.push(quote! {
+                #(#doc_attrs)*
+                #[inline(always)]
+                pub fn #write_fn(&self, #index_param value: #value_ty) {
+                    let reg: #ptr_type = #init_expr;
+                    reg.write(<#field_ty>::from(value))
+                }
+                #(#doc_attrs)*
+                #[inline(always)]
+                pub fn #modify_fn<F>(&self, #index_param f: F)
+                where
+                    F: FnOnce(#value_ty) -> #value_ty,
+                {
+                    let reg: #ptr_type = #init_expr;
+                    reg.modify(|raw| <#field_ty>::from(f(<#value_ty>::from(raw))))
+                }
+                /// Writes `value`, then reads the register back and returns whether it holds
+                /// `value`.
+                #[inline(always)]
+                pub fn #write_verify_fn(&self, #index_param value: #value_ty) -> bool {
+                    let reg: #ptr_type = #init_expr;
+                    reg.write_verify(<#field_ty>::from(value))
+                }
+                /// Like the plain verifying write, but reports failed accesses and what was
+                /// read back.
+                #[inline(always)]
+                pub fn #try_write_verify_fn(
+                    &self,
+                    #index_param
+                    value: #value_ty,
+                ) -> ::core::result::Result<(), #krate::WriteVerifyError<#field_ty>> {
+                    let reg: #ptr_type = #init_expr;
+                    reg.try_write_verify(<#field_ty>::from(value))
+                }
+            }),
+            Access::WO if shadow => struct_fields.push(shadow_fns(&value_ty)),
+            Access::WO => struct_fields.push(quote! {
+                #(#doc_attrs)*
+                #[inline(always)]
+                pub fn #write_fn(&self, #index_param value: #value_ty) {
+                    let reg: #ptr_type = #init_expr;
+                    reg.write(<#field_ty>::from(value))
+                }
+            }),
+            Access::WC => struct_fields.push(quote! {
+                #(#doc_attrs)*
+                #[inline(always)]
+                pub fn #clear_fn(&self, #index_param) {
+                    let reg: #ptr_type = #init_expr;
+                    reg.clear()
+                }
+            }),
+            Access::W1C => struct_fields.push(quote! {
+                #(#doc_attrs)*
+                #[inline(always)]
+                pub fn #ack_fn(&self, #index_param mask: #field_ty) {
+                    let reg: #ptr_type = #init_expr;
+                    reg.ack(mask)
+                }
+            }),
+            Access::RW1C => struct_fields.push(quote! {
+                #(#doc_attrs)*
+                #[inline(always)]
+                pub fn #clear_bits_fn(&self, #index_param mask: #field_ty) {
+                    let reg: #ptr_type = #init_expr;
+                    reg.ack(mask)
+                }
+            }),
+            Access::RO | Access::RC => {}
+        }
+        // masked writes need the bitwise operators of `Bits`
+        if matches!(access, Access::RW) && is_unsigned(field_ty) {
+            struct_fields.push(quote! {
+                #(#doc_attrs)*
+                #[inline(always)]
+                pub fn #write_masked_fn(&self, #index_param value: #value_ty, mask: #field_ty) {
+                    let reg: #ptr_type = #init_expr;
+                    reg.write_masked(<#field_ty>::from(value), mask)
+                }
+            });
+        }
+        // polling RC registers would clear them on every read
+        if matches!(access, Access::RW | Access::RO | Access::W1C | Access::RW1C)
+            && is_unsigned(field_ty)
+        {
+            struct_fields.push(quote! {
+                /// Reads the register until all bits in `mask` are set.
+                pub fn #poll_set_fn(&self, #index_param mask: #field_ty) {
+                    let reg: #ptr_type = #init_expr;
+                    let _ = #krate::__private::poll(|| reg.read(), mask, true, None);
+                }
+                /// Reads the register until all bits in `mask` are clear.
+                pub fn #poll_clear_fn(&self, #index_param mask: #field_ty) {
+                    let reg: #ptr_type = #init_expr;
+                    let _ = #krate::__private::poll(|| reg.read(), mask, false, None);
+                }
+                /// Reads the register until all bits in `mask` are set, at most `iterations`
+                /// times.
+                pub fn #poll_set_timeout_fn(
+                    &self,
+                    #index_param
+                    mask: #field_ty,
+                    iterations: usize,
+                ) -> ::core::result::Result<(), #krate::TimeoutError> {
+                    let reg: #ptr_type = #init_expr;
+                    #krate::__private::poll(|| reg.read(), mask, true, Some(iterations))
+                }
+                /// Reads the register until all bits in `mask` are clear, at most `iterations`
+                /// times.
+                pub fn #poll_clear_timeout_fn(
+                    &self,
+                    #index_param
+                    mask: #field_ty,
+                    iterations: usize,
+                ) -> ::core::result::Result<(), #krate::TimeoutError> {
+                    let reg: #ptr_type = #init_expr;
+                    #krate::__private::poll(|| reg.read(), mask, false, Some(iterations))
+                }
+            });
+        }
+    }
+
+    gate_pending_cfg!();
+    // collisions between conditional registers, checked once the cfgs are known
+    extra_items.append(&mut layout.conditional_errors);
+    extra_items.extend(layout.sub_block_checks());
+
+    if has_reset {
+        struct_fields.push(quote! {
+            /// Writes the reset value of every writable register that declares one, in
+            /// declaration order.
+            pub fn reset_all(&self) {
+                #(#reset_calls)*
+            }
+        });
+    }
+
+    if !init_writes.is_empty() {
+        match init::apply_init(&init_writes, &register_targets) {
+            Ok(apply_init) => struct_fields.push(apply_init),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+    for latch in &latches {
+        match latch.reader(&register_targets) {
+            Ok(reader) => struct_fields.push(reader),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+    for composed in &composes {
+        match composed.reader(&register_targets) {
+            Ok(reader) => struct_fields.push(reader),
+            Err(err) => return err.to_compile_error().into(),
+        }
+        match composed.width_assertion(&register_targets) {
+            Ok(assertion) => extra_items.push(assertion),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+    for snapshot in &snapshots {
+        match snapshot.reader(&register_targets, &krate, no_panic) {
+            Ok(reader) => struct_fields.push(reader),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+
+    if dump_stmts.is_empty() {
+        dump_stmts.push(quote! { let _ = out; });
+    }
+    struct_fields.push(if no_panic {
+        quote! {
+            /// Copies every register that can be read without side effects into `out` at its
+            /// offset, leaving the bytes of RC, WO and WC registers and of unmapped ranges alone.
+            ///
+            /// Does nothing if `out` is shorter than [`Self::BLOCK_SIZE`].
+            pub fn dump(&self, out: &mut [u8]) {
+                if out.len() < Self::BLOCK_SIZE {
+                    return;
+                }
+                #(#dump_stmts)*
+            }
+        }
+    } else {
+        quote! {
+            /// Copies every register that can be read without side effects into `out` at its
+            /// offset, leaving the bytes of RC, WO and WC registers and of unmapped ranges alone.
+            ///
+            /// Panics if `out` is shorter than [`Self::BLOCK_SIZE`].
+            pub fn dump(&self, out: &mut [u8]) {
+                #(#dump_stmts)*
+            }
+        }
+    });
+
+    if let Some((option, rows)) = &verify {
+        match verify::generate(option, struct_name, rows, &verify_entries) {
+            Ok(items) => extra_items.push(items),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+
+    if c_layout {
+        let layout_ty = format_ident!("{}Layout", struct_name);
+        match c_layout::generate(&layout_ty, &input.vis, struct_name, c_layout_entries) {
+            Ok(items) => extra_items.push(items),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+
+    if snapshot {
+        let snapshot_ty = format_ident!("{}Snapshot", struct_name);
+        let vis = &input.vis;
+        let doc = format!(
+            "The values of the side-effect free registers of [`{}`], see [`{}::snapshot`].",
+            struct_name, struct_name
+        );
+        extra_items.push(quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis struct #snapshot_ty {
+                #(#snapshot_fields)*
+            }
+        });
+        struct_fields.push(quote! {
+            /// Reads every register that can be read without side effects, in field order.
+            /// RC, WO and WC registers are left out.
+            pub fn snapshot(&self) -> #snapshot_ty {
+                #snapshot_ty {
+                    #(#snapshot_reads)*
+                }
+            }
+        });
+    }
+
+    if cfg!(feature = "dyn-register") {
+        struct_fields.push(quote! {
+            /// Every register of up to 64 bits as a trait object, one per array element, in
+            /// declaration order. `unchecked` and `allow_any_type` registers are left out.
+            pub fn registers_dyn(&self) -> #krate::dynamic::DynRegisters<'_> {
+                unsafe {
+                    #krate::dynamic::registers(
+                        self.base.base_address(),
+                        Self::REGISTERS,
+                        &[#(#dyn_skipped),*],
+                        &[#(#dyn_big_endian),*],
+                    )
+                }
+            }
+        });
     }
 
+    if derive_debug {
+        let name = struct_name.to_string();
+        extra_items.push(quote! {
+            impl<T: #krate::BaseAddress> ::core::fmt::Debug for #struct_name<T> {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    let mut debug = f.debug_struct(#name);
+                    #(#debug_fields)*
+                    debug.finish()
+                }
+            }
+        });
+    }
+
+    // keep the docs of the input struct and append the memory map
+    let struct_docs = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"));
+    memory_map.sort_by_key(|(offset, ..)| *offset);
+    let map_header = [
+        "",
+        "# Memory map",
+        "",
+        "| Offset | Register | Access | Type | Description |",
+        "|---|---|---|---|---|",
+    ];
+    // rows of conditional fields only show up in the docs when the field exists
+    let map_docs = map_header
+        .iter()
+        .map(|line| quote! { #[doc = #line] })
+        .chain(memory_map.iter().map(|(_, row, cfg)| match cfg {
+            None => quote! { #[doc = #row] },
+            Some(cfg) => quote! { #[cfg_attr(#cfg, doc = #row)] },
+        }));
+    // the table alone, for `MEMORY_MAP`, joined in a const block if some rows are conditional
+    let memory_map_table = if memory_map.iter().all(|(.., cfg)| cfg.is_none()) {
+        let table = map_header[3..]
+            .iter()
+            .copied()
+            .chain(memory_map.iter().map(|(_, row, _)| row.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        quote! { #table }
+    } else {
+        let rows = map_header[3..]
+            .iter()
+            .map(|line| quote! { (#line, true) })
+            .chain(memory_map.iter().map(|(_, row, cfg)| match cfg {
+                None => quote! { (#row, true) },
+                Some(cfg) => quote! { (#row, ::core::cfg!(#cfg)) },
+            }));
+        quote! {{
+            const ROWS: &[(&str, bool)] = &[#(#rows),*];
+            const TABLE: [u8; #krate::__private::joined_len(ROWS)] =
+                #krate::__private::join_rows(ROWS);
+            #krate::__private::utf8(&TABLE)
+        }}
+    };
+    // raw accesses by offset, for registers without a named accessor
+    let peek_message = format!("offset {{:#x}} out of range for `{}`", struct_name);
+    let peek_check = (!no_panic).then(|| {
+        quote! {
+            debug_assert!(
+                offset + ::core::mem::size_of::<V>() <= Self::BLOCK_SIZE,
+                #peek_message,
+                offset
+            );
+        }
+    });
+    let peek_address = offset_address(
+        &krate,
+        no_panic,
+        quote! { self.base.base_address() },
+        quote! { offset },
+    );
+    struct_fields.push(quote! {
+        /// Reads a `V` at `offset` bytes from the base, e.g. an undocumented register.
+        ///
+        /// # Safety
+        /// There must be a register of type `V` at `offset`, and reading it must not break any
+        /// assumptions of the rest of the driver, e.g. by clearing flags.
+        pub unsafe fn peek<V: #krate::MmioPrimitive>(&self, offset: usize) -> V {
+            #peek_check
+            #krate::RO::<V>::new(#peek_address).read()
+        }
+        /// Writes `value` at `offset` bytes from the base, e.g. to an undocumented register.
+        ///
+        /// # Safety
+        /// There must be a register of type `V` at `offset`, and writing it must not break any
+        /// assumptions of the rest of the driver.
+        pub unsafe fn poke<V: #krate::MmioPrimitive>(&self, offset: usize, value: V) {
+            #peek_check
+            #krate::WO::<V>::new(#peek_address).write(value)
+        }
+    });
+    let instance_items = instances.map(|(count, stride)| {
+        let message = format!(
+            "instance {{}} out of range for `{}`, which has {} instances",
+            struct_name, count
+        );
+        let size_message = format!(
+            "the instances of `{}` are {:#x} bytes apart, less than its BLOCK_SIZE",
+            struct_name, stride
+        );
+        // an instance past the last one is someone else's registers, so check in release
+        // builds too, unless the block promises not to panic
+        let (check, panics) = if no_panic {
+            (None, "Out-of-range indices aren't checked.")
+        } else {
+            (
+                Some(quote! { assert!(index < #count, #message, index); }),
+                "Panics if `index` is out of range.",
+            )
+        };
+        let instance_offset = if no_panic {
+            quote! { index.wrapping_mul(#stride) }
+        } else {
+            quote! { index * #stride }
+        };
+        let instance_address = offset_address(
+            &krate,
+            no_panic,
+            quote! { self.base.base_address() },
+            instance_offset,
+        );
+        quote! {
+            impl<T: #krate::BaseAddress> #struct_name<T> {
+                /// Number of instances of the block.
+                pub const INSTANCES: usize = #count;
+                /// Distance between consecutive instances, in bytes.
+                pub const INSTANCE_STRIDE: usize = #stride;
+                /// Instance `index` of the block, counting from the one at `self`.
+                ///
+                #[doc = #panics]
+                pub fn instance(&self, index: usize) -> #struct_name<usize> {
+                    #check
+                    #struct_name::new(#instance_address)
+                }
+            }
+            const _: () = assert!(#struct_name::<usize>::BLOCK_SIZE <= #stride, #size_message);
+        }
+    });
+    let offset_of_message = format!("no register with that name in `{}`", struct_name);
+    let fixed_base_items = fixed_base.map(|address| {
+        let constructor = if exclusive.is_some() {
+            quote! {
+                /// The block at its fixed base address, or `None` if its token has been taken
+                /// before or the table of taken bases is full, see `BaseToken`.
+                pub fn take() -> ::core::option::Option<Self> {
+                    #krate::BaseToken::take().map(Self::from_token)
+                }
+            }
+        } else {
+            quote! {
+                /// The block at its fixed base address.
+                pub const fn at_const() -> Self {
+                    Self::new(#krate::ConstantAddress)
+                }
+            }
+        };
+        quote! {
+            impl #struct_name<#krate::ConstantAddress<#address>> {
+                /// The address given with `base = ...`.
+                pub const BASE: usize = #address;
+                #constructor
+            }
+        }
+    });
+    // with `exclusive`, blocks are built from tokens, and everything else is unsafe
+    let (new_fn, unsafe_new, runtime_base_items, exclusive_items) = if exclusive.is_some() {
+        let new_fn = quote! {
+            /// Create a new register block at the given base address.
+            ///
+            /// # Safety
+            /// No other block may be used at the same base while this one is; use
+            /// `from_token` to have that checked instead.
+            pub const unsafe fn new(base: T)
+        };
+        let exclusive_items = quote! {
+            impl<const ADDR: usize> #struct_name<#krate::ConstantAddress<ADDR>> {
+                /// The block at the address of `token`, which is consumed so that no other
+                /// block can be built from it.
+                pub const fn from_token(_token: #krate::BaseToken<ADDR>) -> Self {
+                    unsafe { Self::new(#krate::ConstantAddress) }
+                }
+            }
+        };
+        (new_fn, true, None, Some(exclusive_items))
+    } else {
+        let runtime_base_items = quote! {
+            impl<T: #krate::RuntimeBase> #struct_name<T> {
+                /// The same block at `base` instead, e.g. once the real address has been found
+                /// while probing.
+                pub fn with_base(self, base: T) -> Self {
+                    Self::new(base)
+                }
+                /// Moves the block to `base`.
+                pub fn set_base(&mut self, base: T) {
+                    self.base = base;
+                }
+            }
+        };
+        let new_fn = quote! {
+            /// Create a new register block at the given base address.
+            pub const fn new(base: T)
+        };
+        (new_fn, false, Some(runtime_base_items), None)
+    };
+    let new_call = |base: proc_macro2::TokenStream| {
+        if unsafe_new {
+            quote! { unsafe { Self::new(#base) } }
+        } else {
+            quote! { Self::new(#base) }
+        }
+    };
+    let from_addr = new_call(quote!(addr));
+    let from_ptr = new_call(quote!(ptr as usize));
+    // the lock's struct field, its initializer in `new()` and `locked`
+    let (lock_field, lock_init, locked_fn) = match &lock {
+        Some(ty) => {
+            let ty = ty
+                .clone()
+                .unwrap_or_else(|| syn::parse_quote!(#krate::SpinLock));
+            (
+                Some(quote! { lock: #ty, }),
+                Some(quote! { lock: <#ty as #krate::RawLock>::INIT, }),
+                Some(quote! {
+                    /// Runs `f` on the block with its lock held, so that sequences run through
+                    /// `locked` from different contexts never interleave. The lock is released
+                    /// even if `f` panics.
+                    ///
+                    /// Accesses made outside of `locked` aren't held up by the lock.
+                    ///
+                    /// The lock lives in the block value: two blocks at the same base don't
+                    /// exclude each other, and `new` or `with_base` start with the lock free.
+                    /// Keep one block per peripheral, shared by reference between the contexts.
+                    pub fn locked<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+                        #krate::__private::with_lock(&self.lock, || f(self))
+                    }
+                }),
+            )
+        }
+        None => (None, None, None),
+    };
     let expanded = quote! {
-        pub struct #struct_name<T: ::register_block::BaseAddress> {
+        #(#struct_docs)*
+        #(#map_docs)*
+        pub struct #struct_name<T: #krate::BaseAddress> {
             base: T,
+            #lock_field
+            #(#shadow_fields)*
         }
-        impl<T: ::register_block::BaseAddress> #struct_name<T> {
-            /// Create a new register block at the given base address.
-            pub fn new(base: T) -> Self {
-                Self { base }
+        impl<T: #krate::BaseAddress> #struct_name<T> {
+            /// Number of bytes spanned by the register block, from the base address to the end
+            /// of its last register.
+            pub const BLOCK_SIZE: usize = {
+                let mut size = 0;
+                #(#block_ends)*
+                size
+            };
+            /// The memory map from the struct docs as a markdown table, one row per register,
+            /// sub-block and reserved range, sorted by offset.
+            pub const MEMORY_MAP: &'static str = #memory_map_table;
+            /// Every register of the block, in declaration order.
+            pub const REGISTERS: &'static [#krate::RegisterInfo] = &[#(#register_infos),*];
+            /// The offset of the register called `name`, of the first element for arrays, or
+            /// `None` if the block has no such register.
+            pub const fn try_offset_of(name: &str) -> ::core::option::Option<usize> {
+                #krate::__private::find_offset(Self::REGISTERS, name)
+            }
+            /// The offset of the register called `name`, of the first element for arrays.
+            ///
+            /// Panics if the block has no such register, which is a compile error in const
+            /// contexts.
+            pub const fn offset_of(name: &str) -> usize {
+                match Self::try_offset_of(name) {
+                    Some(offset) => offset,
+                    None => panic!(#offset_of_message),
+                }
+            }
+            #new_fn -> Self {
+                Self {
+                    base,
+                    #lock_init
+                    #(#shadow_inits)*
+                }
+            }
+            /// The base the block was created with.
+            pub fn base(&self) -> T {
+                self.base
+            }
+            /// The address the registers are laid out from.
+            pub fn base_address(&self) -> usize {
+                self.base.base_address()
             }
+            /// Runs `f` on the block between two `SeqCst` compiler fences, so that the compiler
+            /// can't move memory accesses into or out of the sequence, e.g. a configure, strobe
+            /// and read-back.
+            ///
+            /// Volatile accesses are never reordered with respect to each other anyway; the
+            /// fences order them against ordinary loads and stores around the closure. This is
+            /// a compiler fence only: it emits no barrier instruction, so the CPU and bus can
+            /// still reorder accesses; use `read_acquire`/`write_release` where they must not.
+            pub fn transaction<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+                ::core::sync::atomic::compiler_fence(::core::sync::atomic::Ordering::SeqCst);
+                let result = f(self);
+                ::core::sync::atomic::compiler_fence(::core::sync::atomic::Ordering::SeqCst);
+                result
+            }
+            #locked_fn
             #(#struct_fields)*
         }
+        #runtime_base_items
+        impl #struct_name<usize> {
+            /// Create a register block at the integer address `addr`.
+            ///
+            /// # Safety
+            /// `addr` must be the base of a mapping of this register block for as long as the
+            /// block is used.
+            pub const unsafe fn from_addr(addr: usize) -> Self {
+                #from_addr
+            }
+            /// Create a register block at the address `ptr` points to.
+            ///
+            /// # Safety
+            /// `ptr` must point to a mapping of this register block for as long as the block
+            /// is used.
+            pub unsafe fn from_ptr(ptr: *const u8) -> Self {
+                #from_ptr
+            }
+        }
+        impl<T: #krate::BaseAddress> #krate::RegisterBlockView for #struct_name<T> {
+            fn base_address(&self) -> usize {
+                self.base.base_address()
+            }
+            fn block_size(&self) -> usize {
+                Self::BLOCK_SIZE
+            }
+        }
+        #fixed_base_items
+        #exclusive_items
+        #instance_items
+        #(#extra_items)*
     };
     TokenStream::from(expanded)
 }
+
+/// Derives `BaseAddress` for a domain-specific base type.
+///
+/// A unit struct returns the address given with `#[base(...)]`, a struct with a single field
+/// forwards to that field, which has to implement `BaseAddress` itself (as `usize` does):
+///
+/// ```ignore
+/// #[derive(Clone, Copy, BaseAddress)]
+/// #[base(0x4000_1000)]
+/// struct Uart1Base;
+///
+/// #[derive(Clone, Copy, BaseAddress)]
+/// struct DiscoveredBase(usize);
+/// ```
+///
+/// Like `#[register_block(crate = ...)]`, `#[base_address(crate = path)]` names the path the
+/// crate is reachable under when it isn't `::register_block`.
+#[proc_macro_derive(BaseAddress, attributes(base, base_address))]
+pub fn derive_base_address(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as syn::DeriveInput);
+    match base::derive(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
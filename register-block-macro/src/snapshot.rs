@@ -0,0 +1,175 @@
+//! Registers read together as one consistent value, e.g.
+//! `#[snapshot(group = "time", fields = [subsec, sec])]` on the struct.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Ident, Token};
+
+use crate::init::{self, Target};
+
+/// A read of `fields` as one tuple, in the order listed.
+pub struct Snapshot {
+    group: Ident,
+    fields: Vec<Ident>,
+}
+
+/// Parses every `#[snapshot(group = ..., fields = [...])]` attribute.
+pub fn parse(attrs: &[Attribute]) -> syn::Result<Vec<Snapshot>> {
+    let mut snapshots = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("snapshot")) {
+        let mut group = None;
+        let mut fields = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("group") {
+                let litstr: syn::LitStr = meta.value()?.parse()?;
+                group = Some(litstr.parse::<Ident>()?);
+            } else if meta.path.is_ident("fields") {
+                let content;
+                let value = meta.value()?;
+                syn::bracketed!(content in value);
+                let list = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                fields = Some(list.into_iter().collect::<Vec<_>>());
+            } else {
+                return Err(meta.error("unsupported snapshot option, expected `group` or `fields`"));
+            }
+            Ok(())
+        })?;
+        let (Some(group), Some(fields)) = (group, fields) else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "snapshot reads must have #[snapshot(group = \"...\", fields = [...])]",
+            ));
+        };
+        if fields.len() < 2 {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`fields` must name at least two registers",
+            ));
+        }
+        snapshots.push(Snapshot { group, fields });
+    }
+    Ok(snapshots)
+}
+
+impl Snapshot {
+    /// Generates `read_<group>()`, returning the fields as a tuple in the order listed.
+    ///
+    /// When the fields are plain integer registers that together fill an aligned 2, 4 or 8
+    /// byte range, they are read with a single access covering all of them if the target's
+    /// pointers are that wide and the base is suitably aligned. Otherwise they are read one
+    /// by one, in the order listed.
+    pub fn reader(
+        &self,
+        targets: &[Target],
+        krate: &syn::Path,
+        no_panic: bool,
+    ) -> syn::Result<TokenStream> {
+        let mut parts = Vec::new();
+        for field in &self.fields {
+            let target = init::find(targets, field, "snapshot")?;
+            if let Some(reason) = target.unreadable {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("#[snapshot] can't read `{}`: {}", field, reason),
+                ));
+            }
+            if !target.cfg.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("#[snapshot] can't read `{}`: it is conditional", field),
+                ));
+            }
+            if parts.iter().any(|part: &&Target| part.name == *field) {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("`{}` is listed twice", field),
+                ));
+            }
+            parts.push(target);
+        }
+        let tys: Vec<_> = parts.iter().map(|part| &part.ty).collect();
+        let reads: Vec<_> = parts
+            .iter()
+            .map(|part| {
+                let ptr_type = &part.ptr_type;
+                let init_expr = &part.init_expr;
+                quote! {{
+                    let reg: #ptr_type = #init_expr;
+                    reg.read()
+                }}
+            })
+            .collect();
+        let names: Vec<_> = self
+            .fields
+            .iter()
+            .map(|field| format!("`{}`", field))
+            .collect();
+        let read_fn = format_ident!("read_{}", self.group);
+        let mut docs = vec![format!(
+            "Reads {} as one snapshot, returned in that order.",
+            names.join(", ")
+        )];
+        let body = match wide_access(&parts) {
+            Some((start, len)) => {
+                let wide_ty = format_ident!("u{}", len * 8);
+                let base = quote! { self.base.base_address() };
+                let address =
+                    crate::offset_address(krate, no_panic, base, quote! { #start as usize });
+                let extracts = parts.iter().map(|part| {
+                    let ty = &part.ty;
+                    let at = (part.offset - start) as usize;
+                    let size = part.bytes.unwrap_or_default() as usize;
+                    let len = len as usize;
+                    quote! {
+                        (wide >> (8 * if cfg!(target_endian = "little") {
+                            #at
+                        } else {
+                            #len - #at - #size
+                        })) as #ty
+                    }
+                });
+                docs.push(String::new());
+                docs.push(format!(
+                    "The registers are read with a single {}-bit access where the target's pointers are at least that wide and the block's base is aligned to it, and one by one in that order otherwise.",
+                    len * 8
+                ));
+                quote! {
+                    let address = #address;
+                    if ::core::mem::size_of::<usize>() >= #len as usize && address % #len as usize == 0 {
+                        let wide = unsafe { #krate::RO::<#wide_ty>::new(address) }.read();
+                        (#(#extracts,)*)
+                    } else {
+                        (#(#reads,)*)
+                    }
+                }
+            }
+            None => quote! { (#(#reads,)*) },
+        };
+        Ok(quote! {
+            #(#[doc = #docs])*
+            pub fn #read_fn(&self) -> (#(#tys,)*) {
+                #body
+            }
+        })
+    }
+}
+
+/// The start and length of the aligned range `parts` fill exactly, if one access of that
+/// width can read them all.
+fn wide_access(parts: &[&Target]) -> Option<(u32, u32)> {
+    let mut spans = parts
+        .iter()
+        .map(|part| Some((part.offset, part.bytes?)))
+        .collect::<Option<Vec<_>>>()?;
+    spans.sort_unstable();
+    let start = spans[0].0;
+    let mut end = start;
+    for (offset, bytes) in spans {
+        if offset != end {
+            return None;
+        }
+        end += bytes;
+    }
+    let len = end - start;
+    (matches!(len, 2 | 4 | 8) && start % len == 0).then_some((start, len))
+}
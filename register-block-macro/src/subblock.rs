@@ -0,0 +1,86 @@
+//! Nested register blocks, e.g. `#[subblock(offset = 0x100, ty = ChannelRegs)]`.
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{Attribute, Ident, Type};
+
+/// A field that holds another `#[register_block]` at a fixed offset from the parent.
+pub struct SubBlock {
+    pub offset: u32,
+    pub ty: Type,
+    /// whether the sub-block is a group sharing the parent's base, see [`SubBlock::group`]
+    pub shared: bool,
+}
+
+impl SubBlock {
+    /// Parses `#[subblock(offset = ..., ty = ...)]`; `ty` defaults to the field type.
+    pub fn parse(attr: &Attribute, field_ty: &Type) -> syn::Result<Self> {
+        let mut offset = None;
+        let mut ty = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("offset") {
+                let litint: syn::LitInt = meta.value()?.parse()?;
+                offset = Some(litint.base10_parse::<u32>()?);
+            } else if meta.path.is_ident("ty") {
+                ty = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unsupported subblock option, expected `offset` or `ty`"));
+            }
+            Ok(())
+        })?;
+        let offset = offset.ok_or_else(|| {
+            syn::Error::new_spanned(attr, "missing `offset` in #[subblock(offset = ...)]")
+        })?;
+        Ok(SubBlock {
+            offset,
+            ty: ty.unwrap_or_else(|| field_ty.clone()),
+            shared: false,
+        })
+    }
+
+    /// A field of a `#[register_block(group)]`, which uses the parent's base as is.
+    pub fn group(field_ty: &Type) -> Self {
+        SubBlock {
+            offset: 0,
+            ty: field_ty.clone(),
+            shared: true,
+        }
+    }
+
+    /// The sub-block's base address type, relative to the parent's `T`.
+    fn base_type(&self, krate: &syn::Path) -> TokenStream {
+        if self.shared {
+            return quote! { T };
+        }
+        let offset = Literal::usize_unsuffixed(self.offset as usize);
+        quote! { #krate::OffsetAddress<T, #offset> }
+    }
+
+    pub fn accessor(
+        &self,
+        krate: &syn::Path,
+        name: &Ident,
+        doc_attrs: &[&Attribute],
+    ) -> TokenStream {
+        let ty = &self.ty;
+        let base_type = self.base_type(krate);
+        let base = if self.shared {
+            quote! { self.base }
+        } else {
+            quote! { #krate::OffsetAddress::new(self.base) }
+        };
+        quote! {
+            #(#doc_attrs)*
+            #[inline(always)]
+            pub fn #name(&self) -> #ty<#base_type> {
+                #ty::new(#base)
+            }
+        }
+    }
+
+    /// End of the sub-block within the parent, for `BLOCK_SIZE`.
+    pub fn end(&self) -> TokenStream {
+        let offset = self.offset as usize;
+        let ty = &self.ty;
+        quote! { #offset + #ty::<usize>::BLOCK_SIZE }
+    }
+}
@@ -0,0 +1,138 @@
+//! `#[register_block(verify = [(0x00, 4), (0x04, 4)])]`: the layout checked against an
+//! externally reviewed table of `(offset, width in bytes)`, one row per register. Rows of
+//! register arrays add their element count and, unless it equals the width, their stride:
+//! `(0x10, 4, count = 4, stride = 8)`.
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::parse::ParseStream;
+use syn::{Ident, LitInt, Token, Type};
+
+/// A row of the table.
+pub struct Row {
+    offset: LitInt,
+    width: LitInt,
+    count: Option<LitInt>,
+    stride: Option<LitInt>,
+}
+
+/// A register of the block, in declaration order.
+pub struct Entry {
+    /// `#[cfg]` attributes of the register, which gate its check too
+    pub cfg: TokenStream,
+    pub name: Ident,
+    pub offset_const: Ident,
+    pub ty: Type,
+    /// `count` and `stride` of register arrays
+    pub array: Option<(u32, u32)>,
+}
+
+/// Parses the `[(offset, width, count = ..., stride = ...), ...]` value of the option.
+pub fn parse(input: ParseStream) -> syn::Result<Vec<Row>> {
+    let content;
+    syn::bracketed!(content in input);
+    let rows = content.parse_terminated(
+        |input| {
+            let row;
+            syn::parenthesized!(row in input);
+            let offset = row.parse()?;
+            row.parse::<Token![,]>()?;
+            let width = row.parse()?;
+            let mut count = None;
+            let mut stride = None;
+            while row.parse::<Option<Token![,]>>()?.is_some() && !row.is_empty() {
+                let key: Ident = row.parse()?;
+                row.parse::<Token![=]>()?;
+                if key == "count" {
+                    count = Some(row.parse()?);
+                } else if key == "stride" {
+                    stride = Some(row.parse()?);
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "unsupported verify row option, expected `count` or `stride`",
+                    ));
+                }
+            }
+            if !row.is_empty() {
+                return Err(row.error("expected `,` or the end of the row"));
+            }
+            Ok(Row {
+                offset,
+                width,
+                count,
+                stride,
+            })
+        },
+        Token![,],
+    )?;
+    Ok(rows.into_iter().collect())
+}
+
+/// A `const` item failing to compile if any register's offset, width, count or stride
+/// disagrees with its row of `rows`.
+pub fn generate(
+    option: &syn::Path,
+    block: &Ident,
+    rows: &[Row],
+    entries: &[Entry],
+) -> syn::Result<TokenStream> {
+    if rows.len() != entries.len() {
+        return Err(syn::Error::new_spanned(
+            option,
+            format!(
+                "the verify table has {} rows, but `{}` declares {} registers",
+                rows.len(),
+                block,
+                entries.len()
+            ),
+        ));
+    }
+    let mut asserts = Vec::new();
+    for (row, entry) in rows.iter().zip(entries) {
+        let offset: usize = row.offset.base10_parse()?;
+        let width: usize = row.width.base10_parse()?;
+        let count: u32 = match &row.count {
+            Some(count) => count.base10_parse()?,
+            None => 1,
+        };
+        let stride: Option<u32> = row.stride.as_ref().map(LitInt::base10_parse).transpose()?;
+        let Entry {
+            cfg,
+            name,
+            offset_const,
+            ty,
+            array,
+        } = entry;
+        let mut row_text = format!("({:#04x}, {}", offset, width);
+        if let Some(count) = &row.count {
+            row_text += &format!(", count = {}", count);
+        }
+        if let Some(stride) = &row.stride {
+            row_text += &format!(", stride = {}", stride);
+        }
+        row_text += ")";
+        let message = format!(
+            "`{}` doesn't match its row `{}` of the verify table",
+            name, row_text
+        );
+        // the shape is known here, only the offset and size need the compiler
+        let (entry_count, entry_stride) = array.unwrap_or((1, 0));
+        let shape_matches = entry_count == count
+            && (count == 1 || entry_stride as usize == stride.map_or(width, |s| s as usize));
+        // point at the row, which is more helpful than the whole attribute
+        asserts.push(quote_spanned! {row.offset.span()=>
+            #cfg
+            assert!(
+                #block::<usize>::#offset_const == #offset
+                    && ::core::mem::size_of::<#ty>() == #width
+                    && #shape_matches,
+                #message
+            );
+        });
+    }
+    Ok(quote! {
+        const _: () = {
+            #(#asserts)*
+        };
+    })
+}
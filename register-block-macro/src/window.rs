@@ -0,0 +1,146 @@
+//! Memory-mapped buffers such as a shared SRAM, declared with
+//! `#[window(offset = 0x1000, len = 0x1000)]` on a field, and accessed as byte ranges rather
+//! than as a register.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, Ident};
+
+use crate::layout::Claim;
+
+/// The `len` bytes at `offset`.
+pub struct Window {
+    pub offset: u32,
+    pub len: u32,
+}
+
+impl Window {
+    /// Parses `#[window(offset = ..., len = ...)]`.
+    pub fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let mut offset = None;
+        let mut len = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("offset") {
+                let litint: syn::LitInt = meta.value()?.parse()?;
+                offset = Some(litint.base10_parse::<u32>()?);
+            } else if meta.path.is_ident("len") {
+                let litint: syn::LitInt = meta.value()?.parse()?;
+                len = Some(litint.base10_parse::<u32>()?);
+            } else {
+                return Err(meta.error("unsupported window option, expected `offset` or `len`"));
+            }
+            Ok(())
+        })?;
+        match (offset, len) {
+            (Some(_), Some(0)) => Err(syn::Error::new_spanned(
+                attr,
+                "windows must be at least one byte long",
+            )),
+            (Some(offset), Some(len)) => match offset.checked_add(len) {
+                Some(_) => Ok(Window { offset, len }),
+                None => Err(syn::Error::new_spanned(attr, "window out of range")),
+            },
+            _ => Err(syn::Error::new_spanned(
+                attr,
+                "windows must have #[window(offset = ..., len = ...)]",
+            )),
+        }
+    }
+
+    pub fn end(&self) -> u32 {
+        self.offset + self.len
+    }
+
+    /// The bytes the window occupies, on both sides, under the field's `#[cfg]` predicate.
+    pub fn claim(&self, name: &Ident, cfg: Option<TokenStream>) -> Claim {
+        Claim {
+            start: self.offset,
+            end: self.end(),
+            reads: true,
+            writes: true,
+            name: name.to_string(),
+            reserved: false,
+            cfg,
+            span: name.span(),
+        }
+    }
+
+    /// `<NAME>_OFFSET`, `<NAME>_LEN`, `<name>_read` and `<name>_write`.
+    pub fn accessors(
+        &self,
+        krate: &syn::Path,
+        no_panic: bool,
+        name: &Ident,
+        docs: &[&Attribute],
+    ) -> TokenStream {
+        let upper = name.to_string().to_uppercase();
+        let offset_const = format_ident!("{}_OFFSET", upper);
+        let len_const = format_ident!("{}_LEN", upper);
+        let read_fn = format_ident!("{}_read", name);
+        let write_fn = format_ident!("{}_write", name);
+        let offset = self.offset as usize;
+        let len = self.len as usize;
+        let offset_doc = format!("Offset of the `{}` window from the base address.", name);
+        let len_doc = format!("Length of the `{}` window in bytes.", name);
+        let address = crate::offset_address(
+            krate,
+            no_panic,
+            quote! { self.base.base_address() },
+            quote! { Self::#offset_const + offset },
+        );
+        // `no_panic` blocks skip accesses that don't fit, like `dump()` does
+        let (check, panics) = if no_panic {
+            (
+                quote! {
+                    if offset > Self::#len_const || buf.len() > Self::#len_const - offset {
+                        return;
+                    }
+                },
+                "Does nothing if the range doesn't fit in the window.",
+            )
+        } else {
+            let message = format!(
+                "range {{}}..{{}} out of bounds for the `{}` window of {} bytes",
+                name, len
+            );
+            (
+                quote! {
+                    assert!(
+                        offset <= Self::#len_const && buf.len() <= Self::#len_const - offset,
+                        #message,
+                        offset,
+                        offset.wrapping_add(buf.len()),
+                    );
+                },
+                "Panics if the range doesn't fit in the window.",
+            )
+        };
+        // a blank line between the field's docs and ours
+        let separator = (!docs.is_empty()).then(|| quote! { #[doc = ""] });
+        quote! {
+            #[doc = #offset_doc]
+            pub const #offset_const: usize = #offset;
+            #[doc = #len_doc]
+            pub const #len_const: usize = #len;
+            #(#docs)*
+            #separator
+            /// Fills `buf` from the window, starting `offset` bytes into it, with one volatile
+            /// read per byte.
+            ///
+            #[doc = #panics]
+            pub fn #read_fn(&self, offset: usize, buf: &mut [u8]) {
+                #check
+                unsafe { #krate::__private::read_bytes(#address, buf) }
+            }
+            #(#docs)*
+            #separator
+            /// Copies `buf` into the window, starting `offset` bytes into it, with one volatile
+            /// write per byte.
+            ///
+            #[doc = #panics]
+            pub fn #write_fn(&self, offset: usize, buf: &[u8]) {
+                #check
+                unsafe { #krate::__private::write_bytes(#address, buf) }
+            }
+        }
+    }
+}
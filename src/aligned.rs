@@ -0,0 +1,46 @@
+//! Register pointers with a stricter alignment than their type, for buses that place
+//! narrow registers on wider boundaries.
+use core::ops::Deref;
+
+use crate::{MmioPrimitive, RC, RO, RW, W1C, WC, WO};
+
+/// A register pointer such as [`RW`] whose address must be a multiple of `ALIGN` rather
+/// than of the register type's own alignment, e.g. a `u32` register on a bus that only
+/// decodes 64-bit aligned addresses.
+///
+/// Misaligned addresses are caught by a debug assertion when the pointer is created. Derefs
+/// to the wrapped pointer, so it has the same methods.
+pub struct Aligned<W, const ALIGN: usize>(W);
+
+impl<W, const ALIGN: usize> Deref for Aligned<W, ALIGN> {
+    type Target = W;
+    fn deref(&self) -> &W {
+        &self.0
+    }
+}
+
+macro_rules! impl_aligned {
+    ($($wrapper:ident),*) => {
+        $(
+            impl<T: MmioPrimitive, const ALIGN: usize> Aligned<$wrapper<'_, T>, ALIGN> {
+                /// # Safety
+                /// `address` must point to a valid MMIO register of type `T`, aligned to
+                /// `ALIGN` bytes, for as long as the returned value is used.
+                pub const unsafe fn new(address: usize) -> Self {
+                    const {
+                        assert!(
+                            ALIGN.is_power_of_two() && ALIGN >= core::mem::align_of::<T>(),
+                            "ALIGN must be a power of two no smaller than the alignment of the register type"
+                        )
+                    };
+                    debug_assert!(
+                        address % ALIGN == 0,
+                        "MMIO register address doesn't have the required alignment"
+                    );
+                    Aligned($wrapper::new(address))
+                }
+            }
+        )*
+    };
+}
+impl_aligned!(RO, WO, RW, WC, RC, W1C);
@@ -0,0 +1,89 @@
+//! Whole-array access to registers declared as arrays of integers with `allow_any_type`,
+//! e.g. `id: [u8; 4]`. An array isn't a single bus access, so arrays don't implement
+//! [`MmioPrimitive`]; their pointers access one element at a time instead.
+use crate::{
+    read_volatile, try_read_volatile, try_write_volatile, write_volatile, AccessError,
+    MmioPrimitive, RO, RW, WO,
+};
+
+/// Reads the `N` elements at `ptr`, first to last, with one volatile read each.
+unsafe fn read_elements<T: MmioPrimitive, const N: usize>(ptr: *const [T; N]) -> [T; N] {
+    core::array::from_fn(|index| unsafe { read_volatile(ptr.cast::<T>().add(index)) })
+}
+
+/// Writes the `N` elements of `value` to `ptr`, first to last, with one volatile write each.
+unsafe fn write_elements<T: MmioPrimitive, const N: usize>(ptr: *mut [T; N], value: [T; N]) {
+    for (index, element) in value.into_iter().enumerate() {
+        write_volatile(ptr.cast::<T>().add(index), element);
+    }
+}
+
+/// Like [`read_elements`], stopping at the first failed access.
+unsafe fn try_read_elements<T: MmioPrimitive, const N: usize>(
+    ptr: *const [T; N],
+) -> Result<[T; N], AccessError> {
+    let mut out = [None; N];
+    for (index, slot) in out.iter_mut().enumerate() {
+        *slot = Some(try_read_volatile(ptr.cast::<T>().add(index))?);
+    }
+    Ok(out.map(|element| element.expect("every element was read")))
+}
+
+/// Like [`write_elements`], stopping at the first failed access.
+unsafe fn try_write_elements<T: MmioPrimitive, const N: usize>(
+    ptr: *mut [T; N],
+    value: [T; N],
+) -> Result<(), AccessError> {
+    for (index, element) in value.into_iter().enumerate() {
+        try_write_volatile(ptr.cast::<T>().add(index), element)?;
+    }
+    Ok(())
+}
+
+impl<'a, T: MmioPrimitive, const N: usize> RO<'a, [T; N]> {
+    /// Reads the elements first to last, with one volatile read each.
+    pub fn read(&self) -> [T; N] {
+        unsafe { read_elements(self.0) }
+    }
+    /// Like `read`, but reports the first failed access instead of assuming they succeed.
+    pub fn try_read(&self) -> Result<[T; N], AccessError> {
+        unsafe { try_read_elements(self.0) }
+    }
+}
+
+impl<'a, T: MmioPrimitive, const N: usize> WO<'a, [T; N]> {
+    /// Writes the elements first to last, with one volatile write each.
+    pub fn write(&self, value: [T; N]) {
+        unsafe { write_elements(self.0, value) }
+    }
+    /// Like `write`, but reports the first failed access instead of assuming they succeed.
+    pub fn try_write(&self, value: [T; N]) -> Result<(), AccessError> {
+        unsafe { try_write_elements(self.0, value) }
+    }
+}
+
+impl<'a, T: MmioPrimitive, const N: usize> RW<'a, [T; N]> {
+    /// Reads the elements first to last, with one volatile read each.
+    pub fn read(&self) -> [T; N] {
+        unsafe { read_elements(self.0) }
+    }
+    /// Like `read`, but reports the first failed access instead of assuming they succeed.
+    pub fn try_read(&self) -> Result<[T; N], AccessError> {
+        unsafe { try_read_elements(self.0) }
+    }
+    /// Writes the elements first to last, with one volatile write each.
+    pub fn write(&self, value: [T; N]) {
+        unsafe { write_elements(self.0, value) }
+    }
+    /// Like `write`, but reports the first failed access instead of assuming they succeed.
+    pub fn try_write(&self, value: [T; N]) -> Result<(), AccessError> {
+        unsafe { try_write_elements(self.0, value) }
+    }
+    /// Reads the array, passes it to `f` and writes back the result.
+    pub fn modify<F>(&self, f: F)
+    where
+        F: FnOnce([T; N]) -> [T; N],
+    {
+        self.write(f(self.read()));
+    }
+}
@@ -0,0 +1,138 @@
+//! Atomic MMIO access backed by `core::sync::atomic`.
+//!
+//! These only give real guarantees where both the CPU and the peripheral's bus support
+//! atomic operations on device memory. On many microcontrollers atomic read-modify-write
+//! instructions to MMIO are unsupported or silently split, so check your hardware before
+//! relying on them.
+use core::marker::PhantomData;
+use core::sync::atomic::Ordering;
+
+/// Integer types with a matching atomic type in `core::sync::atomic`.
+///
+/// This trait is sealed and implemented for `u8`, `u16`, `u32`, `u64` and `usize`, each only on
+/// targets with atomics of that width (`target_has_atomic`).
+pub trait AtomicPrimitive: crate::sealed::Sealed + Copy {
+    type Atomic;
+    fn load(atomic: &Self::Atomic, order: Ordering) -> Self;
+    fn store(atomic: &Self::Atomic, value: Self, order: Ordering);
+    fn fetch_or(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self;
+    fn fetch_and(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self;
+    fn fetch_xor(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self;
+    fn compare_exchange_weak(
+        atomic: &Self::Atomic,
+        current: Self,
+        new: Self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self, Self>;
+}
+
+macro_rules! impl_atomic_primitive {
+    ($($ty:ty => $atomic:ty, $width:literal;)*) => {
+        $(
+            #[cfg(target_has_atomic = $width)]
+            impl AtomicPrimitive for $ty {
+                type Atomic = $atomic;
+                fn load(atomic: &Self::Atomic, order: Ordering) -> Self {
+                    atomic.load(order)
+                }
+                fn store(atomic: &Self::Atomic, value: Self, order: Ordering) {
+                    atomic.store(value, order)
+                }
+                fn fetch_or(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self {
+                    atomic.fetch_or(value, order)
+                }
+                fn fetch_and(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self {
+                    atomic.fetch_and(value, order)
+                }
+                fn fetch_xor(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self {
+                    atomic.fetch_xor(value, order)
+                }
+                fn compare_exchange_weak(
+                    atomic: &Self::Atomic,
+                    current: Self,
+                    new: Self,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<Self, Self> {
+                    atomic.compare_exchange_weak(current, new, success, failure)
+                }
+            }
+        )*
+    };
+}
+impl_atomic_primitive! {
+    u8 => core::sync::atomic::AtomicU8, "8";
+    u16 => core::sync::atomic::AtomicU16, "16";
+    u32 => core::sync::atomic::AtomicU32, "32";
+    u64 => core::sync::atomic::AtomicU64, "64";
+    usize => core::sync::atomic::AtomicUsize, "ptr";
+}
+
+/// a MMIO register pointer that can be read and written to atomically
+pub struct AtomicRW<'a, T: AtomicPrimitive>(*const T::Atomic, PhantomData<&'a T::Atomic>);
+#[cfg(feature = "send-sync")]
+unsafe impl<T: AtomicPrimitive> Send for AtomicRW<'_, T> where T::Atomic: Sync {}
+#[cfg(feature = "send-sync")]
+unsafe impl<T: AtomicPrimitive> Sync for AtomicRW<'_, T> where T::Atomic: Sync {}
+impl<'a, T: AtomicPrimitive> AtomicRW<'a, T> {
+    /// # Safety
+    /// `address` must point to a valid MMIO register of type `T`, aligned to the alignment
+    /// of `T`'s atomic type, for as long as the returned value is used.
+    pub const unsafe fn new(address: usize) -> Self {
+        crate::debug_assert_aligned::<T::Atomic>(address);
+        AtomicRW(address as *const T::Atomic, PhantomData)
+    }
+    /// Like [`AtomicRW::new`], but borrows `region` so the pointer can't outlive the mapping.
+    ///
+    /// # Safety
+    /// `address` must point to a valid MMIO register of type `T` inside `region`, aligned to
+    /// the alignment of `T`'s atomic type.
+    pub const unsafe fn with_region<R: ?Sized>(address: usize, _region: &'a R) -> Self {
+        Self::new(address)
+    }
+    fn atomic(&self) -> &T::Atomic {
+        unsafe { &*self.0 }
+    }
+    pub fn load(&self, order: Ordering) -> T {
+        T::load(self.atomic(), order)
+    }
+    pub fn store(&self, value: T, order: Ordering) {
+        T::store(self.atomic(), value, order)
+    }
+    /// Atomically ORs `value` into the register, returning the previous value.
+    pub fn fetch_or(&self, value: T, order: Ordering) -> T {
+        T::fetch_or(self.atomic(), value, order)
+    }
+    /// Atomically ANDs `value` into the register, returning the previous value.
+    pub fn fetch_and(&self, value: T, order: Ordering) -> T {
+        T::fetch_and(self.atomic(), value, order)
+    }
+    /// Atomically XORs `value` into the register, returning the previous value.
+    pub fn fetch_xor(&self, value: T, order: Ordering) -> T {
+        T::fetch_xor(self.atomic(), value, order)
+    }
+    /// Atomically replaces the register with `f(previous)` by retrying a compare-exchange
+    /// until no other writer got in between, like the `fetch_update` of the std atomics.
+    ///
+    /// `f` may be called several times and stops the update by returning `None`. Returns
+    /// `Ok(previous)` once the new value is stored, `Err(previous)` if `f` gave up.
+    pub fn fetch_update<F>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: F,
+    ) -> Result<T, T>
+    where
+        F: FnMut(T) -> Option<T>,
+    {
+        let mut previous = self.load(fetch_order);
+        while let Some(new) = f(previous) {
+            match T::compare_exchange_weak(self.atomic(), previous, new, set_order, fetch_order) {
+                Ok(previous) => return Ok(previous),
+                Err(current) => previous = current,
+            }
+        }
+        Err(previous)
+    }
+}
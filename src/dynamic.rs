@@ -0,0 +1,154 @@
+//! Registers behind a trait object, for tools that treat every register alike, e.g. a
+//! diagnostics shell poking at registers by name.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{Access, RegisterInfo, RO, WO};
+
+/// A register of any width up to 64 bits, with its value widened to `u64`.
+pub trait Register {
+    /// Name of the register, shared by all elements of an array.
+    fn name(&self) -> &'static str;
+    /// Address of the register.
+    fn address(&self) -> usize;
+    /// Width in bits.
+    fn width(&self) -> u32;
+    /// Reads the register, zero-extended to 64 bits. Registers that can't be read give 0
+    /// without accessing the bus.
+    fn read_u64(&self) -> u64;
+    /// Writes the low `width()` bits of `value`. Higher bits must be zero, which is
+    /// debug-asserted. Writes to registers that can't be written are ignored.
+    fn write_u64(&self, value: u64);
+}
+
+/// The [`Register`] behind the trait objects of the generated `registers_dyn()`.
+#[derive(Debug, Clone, Copy)]
+pub struct DynRegister<'a> {
+    name: &'static str,
+    address: usize,
+    width: u32,
+    access: Access,
+    big_endian: bool,
+    _block: PhantomData<&'a ()>,
+}
+
+impl DynRegister<'_> {
+    /// A register whose value is byte-swapped around every access if `big_endian` is set,
+    /// like the accessors of `endian = "big"` registers.
+    ///
+    /// Panics if `width` isn't 8, 16, 32 or 64.
+    ///
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of `width` bits for as
+    /// long as the returned value is used.
+    pub const unsafe fn new(
+        name: &'static str,
+        address: usize,
+        width: u32,
+        access: Access,
+        big_endian: bool,
+    ) -> Self {
+        assert!(
+            matches!(width, 8 | 16 | 32 | 64),
+            "dynamic registers must be 8, 16, 32 or 64 bits wide"
+        );
+        DynRegister {
+            name,
+            address,
+            width,
+            access,
+            big_endian,
+            _block: PhantomData,
+        }
+    }
+}
+
+impl Register for DynRegister<'_> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    fn address(&self) -> usize {
+        self.address
+    }
+    fn width(&self) -> u32 {
+        self.width
+    }
+    fn read_u64(&self) -> u64 {
+        if matches!(self.access, Access::WO | Access::WC) {
+            return 0;
+        }
+        let be = self.big_endian;
+        unsafe {
+            match self.width {
+                8 => RO::<u8>::new(self.address).read() as u64,
+                16 => swap_if(be, RO::<u16>::new(self.address).read(), u16::from_be) as u64,
+                32 => swap_if(be, RO::<u32>::new(self.address).read(), u32::from_be) as u64,
+                _ => swap_if(be, RO::<u64>::new(self.address).read(), u64::from_be),
+            }
+        }
+    }
+    fn write_u64(&self, value: u64) {
+        debug_assert!(
+            self.width >= 64 || value >> self.width == 0,
+            "{:#x} doesn't fit the {}-bit register `{}`",
+            value,
+            self.width,
+            self.name
+        );
+        if matches!(self.access, Access::RO | Access::RC) {
+            return;
+        }
+        let be = self.big_endian;
+        unsafe {
+            match self.width {
+                8 => WO::<u8>::new(self.address).write(value as u8),
+                16 => WO::<u16>::new(self.address).write(swap_if(be, value as u16, u16::to_be)),
+                32 => WO::<u32>::new(self.address).write(swap_if(be, value as u32, u32::to_be)),
+                _ => WO::<u64>::new(self.address).write(swap_if(be, value, u64::to_be)),
+            }
+        }
+    }
+}
+
+/// `swap(value)` for big-endian registers, `value` otherwise: `from_be`/`to_be`, as `BE` does.
+fn swap_if<T>(big_endian: bool, value: T, swap: fn(T) -> T) -> T {
+    if big_endian {
+        swap(value)
+    } else {
+        value
+    }
+}
+
+/// What the generated `registers_dyn()` returns.
+pub type DynRegisters<'a> = Vec<Box<dyn Register + 'a>>;
+
+/// One [`DynRegister`] per register (and array element) of `registers`, skipping registers
+/// wider than 64 bits and those named in `skip`. Those named in `big_endian` are byte-swapped.
+///
+/// # Safety
+/// `registers` must describe the block at `base`, as its `REGISTERS` does.
+pub unsafe fn registers<'a>(
+    base: usize,
+    registers: &[RegisterInfo],
+    skip: &[&str],
+    big_endian: &[&str],
+) -> DynRegisters<'a> {
+    let mut out: DynRegisters<'a> = Vec::new();
+    for info in registers {
+        if !matches!(info.width, 8 | 16 | 32 | 64) || skip.contains(&info.name) {
+            continue;
+        }
+        for index in 0..info.count {
+            let address = base + info.offset + index * info.stride;
+            out.push(Box::new(DynRegister::new(
+                info.name,
+                address,
+                info.width,
+                info.access,
+                big_endian.contains(&info.name),
+            )));
+        }
+    }
+    out
+}
@@ -0,0 +1,139 @@
+//! Big-endian MMIO access for registers presented big-endian regardless of the host.
+use crate::{WriteVerifyError, RC, RO, RW, W1C, WC, WO};
+
+/// Integer types that can be byte-swapped between big-endian and native order.
+///
+/// This trait is sealed and implemented for the integer types (`u8`..`u128`, `usize`,
+/// `i8`..`i128`, `isize`). Signed values swap the bytes of their two's complement pattern.
+pub trait Endian: crate::MmioPrimitive {
+    /// Converts a big-endian value read from a register to native order.
+    fn from_be(raw: Self) -> Self;
+    /// Converts a native value to big-endian order for writing to a register.
+    fn to_be(self) -> Self;
+}
+
+macro_rules! impl_endian {
+    ($($ty:ty),*) => {
+        $(
+            impl Endian for $ty {
+                fn from_be(raw: Self) -> Self {
+                    <$ty>::from_be(raw)
+                }
+                fn to_be(self) -> Self {
+                    <$ty>::to_be(self)
+                }
+            }
+        )*
+    };
+}
+impl_endian!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A big-endian view of a register pointer such as [`RW`] or [`RO`]
+///
+/// Values are byte-swapped around every access on little-endian hosts, so `read` and
+/// `write` always deal in native values.
+pub struct BE<W>(W);
+impl<W> BE<W> {
+    pub const fn new(inner: W) -> Self {
+        BE(inner)
+    }
+}
+
+impl<T: Endian> BE<RO<'_, T>> {
+    pub fn read(&self) -> T {
+        T::from_be(self.0.read())
+    }
+}
+
+impl<T: Endian> BE<WO<'_, T>> {
+    pub fn write(&self, value: T) {
+        self.0.write(value.to_be())
+    }
+}
+
+impl<T: Endian> BE<RW<'_, T>> {
+    pub fn read(&self) -> T {
+        T::from_be(self.0.read())
+    }
+
+    pub fn write(&self, value: T) {
+        self.0.write(value.to_be())
+    }
+
+    pub fn modify<F>(&self, f: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        self.write(f(self.read()));
+    }
+
+    /// Like [`BE::modify`], but only writes the register if `f` returns `Ok`.
+    pub fn try_modify<E, F>(&self, f: F) -> Result<(), E>
+    where
+        F: FnOnce(T) -> Result<T, E>,
+    {
+        self.write(f(self.read())?);
+        Ok(())
+    }
+}
+
+impl<T: Endian + PartialEq> BE<RW<'_, T>> {
+    /// Writes `value`, then reads the register back and returns whether it holds `value`.
+    pub fn write_verify(&self, value: T) -> bool {
+        self.0.write_verify(value.to_be())
+    }
+
+    /// Like [`BE::write_verify`], but reports failed accesses and what was read back.
+    pub fn try_write_verify(&self, value: T) -> Result<(), WriteVerifyError<T>> {
+        self.0
+            .try_write_verify(value.to_be())
+            .map_err(|err| match err {
+                WriteVerifyError::Access(err) => WriteVerifyError::Access(err),
+                WriteVerifyError::Mismatch {
+                    address,
+                    written,
+                    read,
+                } => WriteVerifyError::Mismatch {
+                    address,
+                    written: T::from_be(written),
+                    read: T::from_be(read),
+                },
+            })
+    }
+}
+
+impl<T: Endian + crate::Bits> BE<RW<'_, T>> {
+    /// Replaces the bits selected by `mask` with those of `value` in a single
+    /// read-modify-write, leaving all other bits alone.
+    pub fn write_masked(&self, value: T, mask: T) {
+        self.modify(|old| (old & !mask) | (value & mask));
+    }
+}
+
+impl<T: Endian> BE<WC<'_, T>> {
+    /// Clears the register by writing `value`.
+    pub fn clear_with(&self, value: T) {
+        self.0.clear_with(value.to_be())
+    }
+}
+impl<T: Endian + Default> BE<WC<'_, T>> {
+    pub fn clear(&self) {
+        self.clear_with(self.0 .1.unwrap_or_default())
+    }
+}
+
+impl<T: Endian> BE<RC<'_, T>> {
+    pub fn read(&self) -> T {
+        T::from_be(self.0.read())
+    }
+}
+
+impl<T: Endian> BE<W1C<'_, T>> {
+    pub fn read(&self) -> T {
+        T::from_be(self.0.read())
+    }
+    /// Clears the flags set in `mask` by writing it to the register.
+    pub fn ack(&self, mask: T) {
+        self.0.ack(mask.to_be())
+    }
+}
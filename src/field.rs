@@ -0,0 +1,113 @@
+//! Registers put together at runtime, e.g. by a scripting layer, as the counterpart of the
+//! accessors the macro generates.
+use core::marker::PhantomData;
+
+use crate::{debug_assert_aligned, read_volatile, write_volatile, Access, MmioPrimitive};
+
+/// An access that the [`Access`] of a [`RegisterField`] doesn't allow, e.g. writing a RO
+/// register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessDenied {
+    /// Address of the register.
+    pub address: usize,
+    /// How the register may be accessed.
+    pub access: Access,
+}
+
+impl core::fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:?} register at {:#x} doesn't allow this access",
+            self.access, self.address
+        )
+    }
+}
+
+/// A register of type `T` at an address known only at runtime, whose access type is checked
+/// on every operation rather than by the type system.
+#[derive(Debug, Clone, Copy)]
+pub struct RegisterField<T> {
+    address: usize,
+    access: Access,
+    _ty: PhantomData<T>,
+}
+
+impl<T: MmioPrimitive> RegisterField<T> {
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T` that may
+    /// be accessed as `access` allows, for as long as the returned value is used.
+    /// Misalignment is caught by a debug assertion.
+    pub const unsafe fn new(address: usize, access: Access) -> Self {
+        debug_assert_aligned::<T>(address);
+        RegisterField {
+            address,
+            access,
+            _ty: PhantomData,
+        }
+    }
+
+    /// The register `offset` bytes past `base`.
+    ///
+    /// # Safety
+    /// See [`RegisterField::new`].
+    pub unsafe fn at(base: usize, offset: usize, access: Access) -> Self {
+        Self::new(crate::__private::offset_address(base, offset), access)
+    }
+
+    pub fn address(&self) -> usize {
+        self.address
+    }
+
+    pub fn access(&self) -> Access {
+        self.access
+    }
+
+    fn denied(&self) -> AccessDenied {
+        AccessDenied {
+            address: self.address,
+            access: self.access,
+        }
+    }
+
+    /// Reads the register, which also clears RC registers. Fails for WO and WC registers.
+    pub fn read(&self) -> Result<T, AccessDenied> {
+        match self.access {
+            Access::RW | Access::RO | Access::RC | Access::W1C | Access::RW1C => {
+                Ok(unsafe { read_volatile(self.address as *const T) })
+            }
+            Access::WO | Access::WC => Err(self.denied()),
+        }
+    }
+
+    /// Writes `value`, which clears its set bits in W1C and RW1C registers. Fails for RO,
+    /// RC and WC registers.
+    pub fn write(&self, value: T) -> Result<(), AccessDenied> {
+        match self.access {
+            Access::RW | Access::WO | Access::W1C | Access::RW1C => {
+                unsafe { write_volatile(self.address as *mut T, value) };
+                Ok(())
+            }
+            Access::RO | Access::RC | Access::WC => Err(self.denied()),
+        }
+    }
+}
+
+impl<T: MmioPrimitive + Default> RegisterField<T> {
+    /// Clears the register the way its access type does: WC registers by writing
+    /// `Default::default()`, RC registers by reading and discarding the value. Fails for
+    /// every other access type.
+    pub fn clear(&self) -> Result<(), AccessDenied> {
+        match self.access {
+            Access::WC => {
+                unsafe { write_volatile(self.address as *mut T, T::default()) };
+                Ok(())
+            }
+            Access::RC => {
+                unsafe { read_volatile(self.address as *const T) };
+                Ok(())
+            }
+            _ => Err(self.denied()),
+        }
+    }
+}
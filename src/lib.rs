@@ -1,5 +1,270 @@
 //! Re-exports the register_block macro and provides the BaseAddress trait and FixedAddress type.
-pub use register_block_macro::register_block;
+//!
+//! ```
+//! use register_block::register_block;
+//!
+//! #[register_block]
+//! pub struct TestRegs {
+//!     #[register(offset = 0x00, access = "RW")]
+//!     reg0: u32,
+//!     #[register(offset = 0x04, access = "RO")]
+//!     reg1: u32,
+//! }
+//!
+//! assert_eq!(TestRegs::<usize>::REG0_OFFSET, 0x00);
+//! assert_eq!(TestRegs::<usize>::REG1_OFFSET, 0x04);
+//! ```
+//!
+//! # Features
+//!
+//! - `send-sync`: implements `Send` and `Sync` for the register wrappers. **The wrappers do no
+//!   synchronisation whatsoever**: enabling this makes you responsible for making sure
+//!   concurrent accesses to a register, e.g. from an interrupt handler and the main loop,
+//!   can't conflict.
+//! - `mock`: lets tests route register accesses to an in-memory backend (the `mock` module).
+//!   Implies `std`.
+//! - `std`: links `std`, which the crate otherwise does without. On Linux this adds
+//!   `MappedBlock`, which maps a register block from `/dev/mem` or a file and unmaps it on
+//!   drop.
+//! - `dyn-register`: generates `registers_dyn()` on every block, handing out its registers as
+//!   `Box<dyn Register>` trait objects that widen values to `u64`. Needs an allocator.
+//! - `critical-section`: adds [`GlobalCs`], a [`CriticalSection`] entered with
+//!   `critical_section::with`, for `cs = "register_block::GlobalCs"` and `modify_cs`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "dyn-register")]
+extern crate alloc;
+
+use core::marker::PhantomData;
+
+pub use register_block_macro::{register_block, BaseAddress};
+
+#[doc(hidden)]
+pub mod __private {
+    //! Helpers for code generated by the `register_block` macro, not public API.
+    use core::fmt;
+
+    #[cfg(feature = "lock")]
+    pub use crate::lock::with_lock;
+
+    /// Formats a register value as hex.
+    pub struct Hex<T>(pub T);
+    impl<T: fmt::LowerHex> fmt::Debug for Hex<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:#x}", self.0)
+        }
+    }
+
+    /// `base + offset`, panicking in debug builds if that lies past the end of the address
+    /// space instead of wrapping around.
+    #[inline(always)]
+    #[track_caller]
+    pub fn offset_address(base: usize, offset: usize) -> usize {
+        match base.checked_add(offset) {
+            Some(address) => address,
+            None => {
+                if cfg!(debug_assertions) {
+                    panic!(
+                        "register at offset {:#x} from base {:#x} lies past the end of the address space",
+                        offset, base
+                    );
+                }
+                base.wrapping_add(offset)
+            }
+        }
+    }
+
+    /// The primitive integer types, for checking registers typed through an alias.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` is not a primitive integer type",
+        note = "registers with a type alias must alias an integer type, or use `allow_any_type`"
+    )]
+    pub trait Integer {}
+    macro_rules! impl_integer {
+        ($($ty:ty),*) => {
+            $(impl Integer for $ty {})*
+        };
+    }
+    impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+    /// Fails to compile unless `T` is a primitive integer type.
+    pub const fn assert_integer<T: Integer>() {}
+
+    /// `a == b`, in const contexts.
+    pub const fn str_eq(a: &str, b: &str) -> bool {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// The offset of the register called `name` in `registers`.
+    pub const fn find_offset(registers: &[crate::RegisterInfo], name: &str) -> Option<usize> {
+        let mut i = 0;
+        while i < registers.len() {
+            if str_eq(registers[i].name, name) {
+                return Some(registers[i].offset);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// The length of the `rows` marked present, joined by newlines, for `MEMORY_MAP`s with
+    /// conditional rows.
+    pub const fn joined_len(rows: &[(&str, bool)]) -> usize {
+        let mut len = 0;
+        let mut first = true;
+        let mut i = 0;
+        while i < rows.len() {
+            if rows[i].1 {
+                if !first {
+                    len += 1;
+                }
+                first = false;
+                len += rows[i].0.len();
+            }
+            i += 1;
+        }
+        len
+    }
+
+    /// The `rows` marked present, joined by newlines. `N` must be their [`joined_len`].
+    pub const fn join_rows<const N: usize>(rows: &[(&str, bool)]) -> [u8; N] {
+        let mut out = [0; N];
+        let mut at = 0;
+        let mut first = true;
+        let mut i = 0;
+        while i < rows.len() {
+            let (row, present) = rows[i];
+            if present {
+                if !first {
+                    out[at] = b'\n';
+                    at += 1;
+                }
+                first = false;
+                let bytes = row.as_bytes();
+                let mut j = 0;
+                while j < bytes.len() {
+                    out[at] = bytes[j];
+                    at += 1;
+                    j += 1;
+                }
+            }
+            i += 1;
+        }
+        out
+    }
+
+    /// `bytes` as a `str`, in const contexts.
+    pub const fn utf8(bytes: &'static [u8]) -> &'static str {
+        match core::str::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(_) => panic!("invalid UTF-8"),
+        }
+    }
+
+    /// Fills `buf` from the memory at `address` with one volatile byte read per byte, for
+    /// `#[window]` reads.
+    ///
+    /// # Safety
+    /// `buf.len()` bytes at `address` must be readable MMIO memory.
+    pub unsafe fn read_bytes(address: usize, buf: &mut [u8]) {
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte = crate::read_volatile((address + i) as *const u8);
+        }
+    }
+
+    /// Copies `buf` to the memory at `address` with one volatile byte write per byte, for
+    /// `#[window]` writes.
+    ///
+    /// # Safety
+    /// `buf.len()` bytes at `address` must be writable MMIO memory.
+    pub unsafe fn write_bytes(address: usize, buf: &[u8]) {
+        for (i, byte) in buf.iter().enumerate() {
+            crate::write_volatile((address + i) as *mut u8, *byte);
+        }
+    }
+
+    /// Copies the bytes of `value` into `out` at `offset`, for `dump()`.
+    pub fn copy_bytes<T>(out: &mut [u8], offset: usize, value: T) {
+        let size = core::mem::size_of::<T>();
+        let bytes = unsafe { core::slice::from_raw_parts((&value as *const T).cast::<u8>(), size) };
+        out[offset..offset + size].copy_from_slice(bytes);
+    }
+
+    /// Calls `read` until the bits in `mask` are all set (or all clear), at most `limit`
+    /// times if given, spinning between reads.
+    pub fn poll<T: crate::Bits>(
+        mut read: impl FnMut() -> T,
+        mask: T,
+        set: bool,
+        limit: Option<usize>,
+    ) -> Result<(), crate::TimeoutError> {
+        let want = if set { mask } else { T::ZERO };
+        let mut reads = 0;
+        loop {
+            if limit.is_some_and(|limit| reads >= limit) {
+                return Err(crate::TimeoutError);
+            }
+            if read() & mask == want {
+                return Ok(());
+            }
+            reads += 1;
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Formats `len` register values, produced by reading index `0..len`, as a hex list.
+    pub struct HexList<F>(pub usize, pub F);
+    impl<T: fmt::LowerHex, F: Fn(usize) -> T> fmt::Debug for HexList<F> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list()
+                .entries((0..self.0).map(|index| Hex((self.1)(index))))
+                .finish()
+        }
+    }
+}
+
+mod aligned;
+pub use aligned::Aligned;
+mod array;
+mod atomic;
+pub use atomic::{AtomicPrimitive, AtomicRW};
+mod field;
+pub use field::{AccessDenied, RegisterField};
+#[cfg(target_has_atomic = "ptr")]
+mod token;
+#[cfg(target_has_atomic = "ptr")]
+pub use token::BaseToken;
+mod endian;
+pub use endian::{Endian, BE};
+mod split;
+pub use split::{Split, WordOrder};
+#[cfg(feature = "dyn-register")]
+pub mod dynamic;
+#[cfg(feature = "dyn-register")]
+pub use dynamic::{DynRegister, Register};
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod mapped;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use mapped::MappedBlock;
+#[cfg(feature = "lock")]
+mod lock;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "lock")]
+pub use lock::RawLock;
+#[cfg(all(feature = "lock", target_has_atomic = "8"))]
+pub use lock::SpinLock;
 
 /// Trait for types that can provide a base address for a register block.
 pub trait BaseAddress: Copy {
@@ -12,6 +277,73 @@ impl BaseAddress for usize {
     }
 }
 
+impl BaseAddress for *const u8 {
+    fn base_address(self) -> usize {
+        self as usize
+    }
+}
+
+impl BaseAddress for *mut u8 {
+    fn base_address(self) -> usize {
+        self as usize
+    }
+}
+
+impl BaseAddress for core::ptr::NonNull<u8> {
+    fn base_address(self) -> usize {
+        self.as_ptr() as usize
+    }
+}
+
+impl<B: BaseAddress> BaseAddress for &B {
+    fn base_address(self) -> usize {
+        (*self).base_address()
+    }
+}
+
+/// A base address patched in at runtime, e.g. once it has been discovered from the device
+/// tree during init.
+///
+/// The address is loaded with `Ordering::Relaxed` on every access: it only has to be stored
+/// before the block is used, any ordering with respect to other memory is up to the caller.
+#[cfg(target_has_atomic = "ptr")]
+impl BaseAddress for &core::sync::atomic::AtomicUsize {
+    fn base_address(self) -> usize {
+        self.load(core::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// A base address computed by calling `F`, e.g. to page an MMIO window in before handing out
+/// its address.
+///
+/// `F` is called every time the address of a register is computed: on every accessor call,
+/// every flat `read_<field>`/`write_<field>` and every `base_address()`, so it should be cheap.
+/// A wrapper returned by an accessor keeps the address it was created with.
+#[derive(Clone, Copy)]
+pub struct DynamicBase<F: Fn() -> usize + Copy>(pub F);
+impl<F: Fn() -> usize + Copy> DynamicBase<F> {
+    /// A base whose address is `f()`, usable in `const` and `static` initialisers when `f` is
+    /// a plain `fn` or a closure that captures nothing.
+    pub const fn new(f: F) -> Self {
+        DynamicBase(f)
+    }
+}
+impl<F: Fn() -> usize + Copy> BaseAddress for DynamicBase<F> {
+    fn base_address(self) -> usize {
+        (self.0)()
+    }
+}
+
+/// Base addresses that are plain runtime values, so a block built on one can be moved to
+/// another base with `with_base` or `set_base`.
+///
+/// Not implemented for [`ConstantAddress`], whose address is part of the type.
+pub trait RuntimeBase: BaseAddress {}
+impl RuntimeBase for usize {}
+impl RuntimeBase for *const u8 {}
+impl RuntimeBase for *mut u8 {}
+impl RuntimeBase for core::ptr::NonNull<u8> {}
+
 /// Zero-sized type for compile-time constant base addresses.
 #[derive(Debug, Clone, Copy)]
 pub struct ConstantAddress<const BASE: usize>;
@@ -21,40 +353,476 @@ impl<const BASE: usize> BaseAddress for ConstantAddress<BASE> {
     }
 }
 
+/// A base address `OFF` bytes past another base address.
+///
+/// Useful to derive the base of a sub-block from the base of its parent.
+#[derive(Debug, Clone, Copy)]
+pub struct OffsetAddress<B: BaseAddress, const OFF: usize>(pub B);
+impl<B: BaseAddress, const OFF: usize> OffsetAddress<B, OFF> {
+    pub const fn new(inner: B) -> Self {
+        OffsetAddress(inner)
+    }
+}
+impl<B: BaseAddress, const OFF: usize> BaseAddress for OffsetAddress<B, OFF> {
+    fn base_address(self) -> usize {
+        __private::offset_address(self.0.base_address(), OFF)
+    }
+}
+impl<B: RuntimeBase, const OFF: usize> RuntimeBase for OffsetAddress<B, OFF> {}
+
+/// Splits a region at `AT` bytes into the bases of its low and high halves, e.g. for two
+/// drivers sharing one mapping.
+///
+/// Nothing checks that the block built on the low half ends before `AT`, or that the high
+/// half fits the region: compare their `BLOCK_SIZE`s against `AT` and the region size, e.g.
+/// in a `const` assertion.
+pub const fn split_at<B: BaseAddress, const AT: usize>(base: B) -> (B, OffsetAddress<B, AT>) {
+    (base, OffsetAddress(base))
+}
+
+/// A way to keep interrupt handlers (and other cores) from running for the duration of a
+/// closure, for read-modify-writes of registers they share, see [`RW::modify_cs`].
+///
+/// With the `critical-section` feature, [`GlobalCs`] is one backed by the `critical-section`
+/// crate.
+pub trait CriticalSection {
+    /// Runs `f` inside the critical section.
+    fn with<R>(f: impl FnOnce() -> R) -> R;
+}
+
+/// The critical section of the `critical-section` crate, entered with
+/// `critical_section::with`, so it masks interrupts the way the target's implementation does.
+#[cfg(feature = "critical-section")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlobalCs;
+
+#[cfg(feature = "critical-section")]
+impl CriticalSection for GlobalCs {
+    fn with<R>(f: impl FnOnce() -> R) -> R {
+        critical_section::with(|_| f())
+    }
+}
+
+/// Common view of every block generated by `#[register_block]`, for code that is generic over
+/// peripherals.
+///
+/// Only has methods, so it can be used as `&dyn RegisterBlockView` too.
+pub trait RegisterBlockView {
+    /// The address the registers of the block are laid out from.
+    fn base_address(&self) -> usize;
+    /// The block's `BLOCK_SIZE`: the number of bytes from the base address to the end of the
+    /// last register.
+    fn block_size(&self) -> usize;
+}
+
+/// Debug-asserts that `address` is suitably aligned for a `U`.
+#[inline(always)]
+pub(crate) const fn debug_assert_aligned<U>(address: usize) {
+    debug_assert!(
+        address % core::mem::align_of::<U>() == 0,
+        "misaligned MMIO register address"
+    );
+}
+
+// Raw pointers make the wrappers `!Send + !Sync`. With the `send-sync` feature they can be
+// moved into (and shared with) interrupt handlers and other threads, but ruling out
+// conflicting concurrent accesses to the hardware is then entirely up to the user.
+macro_rules! impl_send_sync {
+    ($($wrapper:ident),*) => {
+        $(
+            #[cfg(feature = "send-sync")]
+            unsafe impl<T: Send> Send for $wrapper<'_, T> {}
+            #[cfg(feature = "send-sync")]
+            unsafe impl<T: Send> Sync for $wrapper<'_, T> {}
+        )*
+    };
+}
+impl_send_sync!(RO, WO, RW, WC, RC, W1C);
+impl_send_sync!(UnalignedRO, UnalignedWO, UnalignedRW);
+
+/// The fences that go before and after a read (`is_read`) or write with `order`.
+///
+/// Orderings that don't apply to the access fall back to `SeqCst` in release builds.
+fn ordered_fences(
+    order: core::sync::atomic::Ordering,
+    is_read: bool,
+) -> (
+    Option<core::sync::atomic::Ordering>,
+    Option<core::sync::atomic::Ordering>,
+) {
+    use core::sync::atomic::Ordering::*;
+    match (order, is_read) {
+        (Relaxed, _) => (None, None),
+        (Acquire, true) => (None, Some(Acquire)),
+        (Release, false) => (Some(Release), None),
+        (SeqCst, _) => (Some(SeqCst), Some(SeqCst)),
+        _ => {
+            debug_assert!(
+                false,
+                "{:?} is not a valid ordering for a register {}",
+                order,
+                if is_read { "read" } else { "write" }
+            );
+            (Some(SeqCst), Some(SeqCst))
+        }
+    }
+}
+
+#[inline(always)]
+fn fence(order: Option<core::sync::atomic::Ordering>) {
+    if let Some(order) = order {
+        core::sync::atomic::fence(order);
+    }
+}
+
+/// Volatile read of a register, or a read from the mock backend if one is installed.
+#[inline(always)]
+unsafe fn read_volatile<T>(ptr: *const T) -> T {
+    #[cfg(feature = "mock")]
+    if let Some(value) = mock::read(ptr) {
+        return value;
+    }
+    ptr.read_volatile()
+}
+
+/// Volatile write of a register, or a write to the mock backend if one is installed.
+#[inline(always)]
+unsafe fn write_volatile<T>(ptr: *mut T, value: T) {
+    #[cfg(feature = "mock")]
+    let Some(value) = mock::write(ptr, value) else {
+        return;
+    };
+    ptr.write_volatile(value)
+}
+
+// `{:p}` prints the address a wrapper points at
+macro_rules! impl_pointer {
+    ($($wrapper:ident),*) => {
+        $(
+            impl<T> core::fmt::Pointer for $wrapper<'_, T> {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    core::fmt::Pointer::fmt(&self.0, f)
+                }
+            }
+        )*
+    };
+}
+impl_pointer!(RO, WO, RW, WC, RC, W1C);
+impl_pointer!(UnalignedRO, UnalignedWO, UnalignedRW);
+
+/// Fallible [`read_volatile`]: plain volatile reads always succeed, mock backends may fail.
+#[inline(always)]
+unsafe fn try_read_volatile<T>(ptr: *const T) -> Result<T, AccessError> {
+    #[cfg(feature = "mock")]
+    if let Some(result) = mock::try_read(ptr) {
+        return result;
+    }
+    Ok(ptr.read_volatile())
+}
+
+/// Fallible [`write_volatile`]: plain volatile writes always succeed, mock backends may fail.
+#[inline(always)]
+unsafe fn try_write_volatile<T>(ptr: *mut T, value: T) -> Result<(), AccessError> {
+    #[cfg(feature = "mock")]
+    let value = match mock::try_write(ptr, value) {
+        Ok(result) => return result,
+        Err(value) => value,
+    };
+    ptr.write_volatile(value);
+    Ok(())
+}
+
+/// An MMIO access that failed.
+///
+/// Accesses to real hardware never fail; this is reported by backends of simulated
+/// environments, e.g. a mock backend injecting faults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessError {
+    /// Address of the register that couldn't be accessed.
+    pub address: usize,
+}
+
+impl core::fmt::Display for AccessError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "MMIO access to {:#x} failed", self.address)
+    }
+}
+
+/// A write that didn't stick, see [`RW::try_write_verify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteVerifyError<T> {
+    /// Writing or reading back the register failed.
+    Access(AccessError),
+    /// The register reads back a different value than was written, e.g. because some of its
+    /// bits are read-only or the register isn't connected.
+    Mismatch {
+        /// Address of the register.
+        address: usize,
+        written: T,
+        read: T,
+    },
+}
+
+impl<T> From<AccessError> for WriteVerifyError<T> {
+    fn from(err: AccessError) -> Self {
+        WriteVerifyError::Access(err)
+    }
+}
+
+/// A value too large for the bitfield it was meant for, see the generated `try_set_<field>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldOverflow {
+    /// The value that was passed.
+    pub value: u128,
+    /// The largest value the field holds.
+    pub max: u128,
+}
+
+impl core::fmt::Display for FieldOverflow {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:#x} doesn't fit in a bitfield holding at most {:#x}",
+            self.value, self.max
+        )
+    }
+}
+
+/// A poll that gave up before the register reached the wanted state, see
+/// [`RW::poll_set_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutError;
+
+impl core::fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("timed out polling an MMIO register")
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Display for WriteVerifyError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WriteVerifyError::Access(err) => err.fmt(f),
+            WriteVerifyError::Mismatch {
+                address,
+                written,
+                read,
+            } => write!(
+                f,
+                "register at {:#x} reads back {:#x?} after writing {:#x?}",
+                address, read, written
+            ),
+        }
+    }
+}
+
+/// How a register may be accessed, as declared with `access = "..."`.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    RW,
+    RO,
+    WO,
+    WC,
+    RC,
+    W1C,
+    RW1C,
+}
+
+/// Describes one register (or register array) of a block, see the `REGISTERS` associated
+/// const generated by the macro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterInfo {
+    pub name: &'static str,
+    /// Offset from the base address, of the first element for arrays.
+    pub offset: usize,
+    /// Width in bits.
+    pub width: u32,
+    pub access: Access,
+    /// Number of elements, 1 for plain registers.
+    pub count: usize,
+    /// Distance between elements in bytes, the size of the register for plain registers.
+    pub stride: usize,
+    /// The declared `reset` value of integer registers, as it sits in memory: byte-swapped
+    /// for `endian = "big"` registers, and sign-extended for signed ones.
+    pub reset: Option<u128>,
+}
+
+/// `T` with an alignment of 1, so registers at any address can be accessed through it.
+#[repr(C, packed)]
+struct Packed<T>(T);
+
 /// a MMIO register pointer that can be read
-pub struct RO<T>(*const T);
-impl<T> RO<T> {
-    pub unsafe fn new(address: usize) -> Self {
-        RO(address as *const T)
+///
+/// The lifetime ties the pointer to the memory-mapped region it points into; see
+/// [`RO::with_region`].
+pub struct RO<'a, T>(*const T, PhantomData<&'a T>);
+impl<'a, T> RO<'a, T> {
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// for as long as the returned value is used. Misalignment is caught by a debug
+    /// assertion.
+    pub const unsafe fn new(address: usize) -> Self {
+        debug_assert_aligned::<T>(address);
+        RO(address as *const T, PhantomData)
+    }
+    /// Like [`RO::new`], but borrows `region` so the pointer can't outlive the mapping.
+    ///
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// inside `region`.
+    pub const unsafe fn with_region<R: ?Sized>(address: usize, _region: &'a R) -> Self {
+        Self::new(address)
     }
+}
+
+impl<T: MmioPrimitive> RO<'_, T> {
     pub fn read(&self) -> T {
-        unsafe { self.0.read_volatile() }
+        unsafe { read_volatile(self.0) }
+    }
+    /// Reads the register, reporting a failed access instead of assuming it succeeds.
+    pub fn try_read(&self) -> Result<T, AccessError> {
+        unsafe { try_read_volatile(self.0) }
+    }
+    /// Reads the register, then issues an acquire fence so that later memory accesses can't
+    /// be reordered before the read.
+    pub fn read_acquire(&self) -> T {
+        let value = self.read();
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Acquire);
+        value
+    }
+    /// Reads the register with fences for `order`: an acquire fence after the read for
+    /// `Acquire`, fences on both sides for `SeqCst` and none for `Relaxed`. `Release` and
+    /// `AcqRel` make no sense for a read and are caught by a debug assertion.
+    pub fn read_ordered(&self, order: core::sync::atomic::Ordering) -> T {
+        let (before, after) = ordered_fences(order, true);
+        fence(before);
+        let value = self.read();
+        fence(after);
+        value
     }
 }
 
 /// a MMIO register pointer that can be written to
-pub struct WO<T>(*mut T);
-impl<T> WO<T> {
-    pub unsafe fn new(address: usize) -> Self {
-        WO(address as *mut T)
+pub struct WO<'a, T>(*mut T, PhantomData<&'a mut T>);
+impl<'a, T> WO<'a, T> {
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// for as long as the returned value is used. Misalignment is caught by a debug
+    /// assertion.
+    pub const unsafe fn new(address: usize) -> Self {
+        debug_assert_aligned::<T>(address);
+        WO(address as *mut T, PhantomData)
+    }
+    /// Like [`WO::new`], but borrows `region` so the pointer can't outlive the mapping.
+    ///
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// inside `region`.
+    pub const unsafe fn with_region<R: ?Sized>(address: usize, _region: &'a R) -> Self {
+        Self::new(address)
     }
+}
+
+impl<T: MmioPrimitive> WO<'_, T> {
     pub fn write(&self, value: T) {
-        unsafe { self.0.write_volatile(value) }
+        unsafe { write_volatile(self.0, value) }
+    }
+    /// Writes the register, reporting a failed access instead of assuming it succeeds.
+    pub fn try_write(&self, value: T) -> Result<(), AccessError> {
+        unsafe { try_write_volatile(self.0, value) }
+    }
+    /// Issues a release fence, then writes the register, so that earlier memory accesses
+    /// can't be reordered after the write.
+    pub fn write_release(&self, value: T) {
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
+        self.write(value)
+    }
+    /// Writes the register with fences for `order`: a release fence before the write for
+    /// `Release`, fences on both sides for `SeqCst` and none for `Relaxed`. `Acquire` and
+    /// `AcqRel` make no sense for a write and are caught by a debug assertion.
+    pub fn write_ordered(&self, value: T, order: core::sync::atomic::Ordering) {
+        let (before, after) = ordered_fences(order, false);
+        fence(before);
+        self.write(value);
+        fence(after);
     }
 }
 
 /// a MMIO register pointer that can be read and written to
-pub struct RW<T>(*mut T);
-impl<T> RW<T> {
-    pub unsafe fn new(address: usize) -> Self {
-        RW(address as *mut T)
+pub struct RW<'a, T>(*mut T, PhantomData<&'a mut T>);
+impl<'a, T> RW<'a, T> {
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// for as long as the returned value is used. Misalignment is caught by a debug
+    /// assertion.
+    pub const unsafe fn new(address: usize) -> Self {
+        debug_assert_aligned::<T>(address);
+        RW(address as *mut T, PhantomData)
     }
+    /// Like [`RW::new`], but borrows `region` so the pointer can't outlive the mapping.
+    ///
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// inside `region`.
+    pub const unsafe fn with_region<R: ?Sized>(address: usize, _region: &'a R) -> Self {
+        Self::new(address)
+    }
+}
+
+impl<T: MmioPrimitive> RW<'_, T> {
     pub fn read(&self) -> T {
-        unsafe { self.0.read_volatile() }
+        unsafe { read_volatile(self.0) }
     }
 
     pub fn write(&self, value: T) {
-        unsafe { self.0.write_volatile(value) }
+        unsafe { write_volatile(self.0, value) }
+    }
+
+    /// Reads the register, reporting a failed access instead of assuming it succeeds.
+    pub fn try_read(&self) -> Result<T, AccessError> {
+        unsafe { try_read_volatile(self.0) }
+    }
+
+    /// Writes the register, reporting a failed access instead of assuming it succeeds.
+    pub fn try_write(&self, value: T) -> Result<(), AccessError> {
+        unsafe { try_write_volatile(self.0, value) }
+    }
+
+    /// Reads the register, then issues an acquire fence so that later memory accesses can't
+    /// be reordered before the read.
+    pub fn read_acquire(&self) -> T {
+        let value = self.read();
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Acquire);
+        value
+    }
+
+    /// Reads the register with fences for `order`: an acquire fence after the read for
+    /// `Acquire`, fences on both sides for `SeqCst` and none for `Relaxed`. `Release` and
+    /// `AcqRel` make no sense for a read and are caught by a debug assertion.
+    pub fn read_ordered(&self, order: core::sync::atomic::Ordering) -> T {
+        let (before, after) = ordered_fences(order, true);
+        fence(before);
+        let value = self.read();
+        fence(after);
+        value
+    }
+
+    /// Issues a release fence, then writes the register, so that earlier memory accesses
+    /// can't be reordered after the write.
+    pub fn write_release(&self, value: T) {
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
+        self.write(value)
+    }
+
+    /// Writes the register with fences for `order`: a release fence before the write for
+    /// `Release`, fences on both sides for `SeqCst` and none for `Relaxed`. `Acquire` and
+    /// `AcqRel` make no sense for a write and are caught by a debug assertion.
+    pub fn write_ordered(&self, value: T, order: core::sync::atomic::Ordering) {
+        let (before, after) = ordered_fences(order, false);
+        fence(before);
+        self.write(value);
+        fence(after);
     }
 
     pub fn modify<F>(&self, f: F)
@@ -63,27 +831,404 @@ impl<T> RW<T> {
     {
         self.write(f(self.read()));
     }
+
+    /// Like [`RW::modify`], but `f` may reject the update: the register is only written if
+    /// `f` returns `Ok`, and an `Err` is passed on without touching it.
+    pub fn try_modify<E, F>(&self, f: F) -> Result<(), E>
+    where
+        F: FnOnce(T) -> Result<T, E>,
+    {
+        self.write(f(self.read())?);
+        Ok(())
+    }
+
+    /// Like [`RW::modify`], but inside the critical section `C`, so an interrupt handler
+    /// sharing the register can't run between the read and the write.
+    pub fn modify_cs<C: CriticalSection, F>(&self, f: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        C::with(|| self.modify(f))
+    }
+}
+
+impl<T: MmioPrimitive + PartialEq> RW<'_, T> {
+    /// Writes `value`, then reads the register back and returns whether it holds `value`.
+    ///
+    /// Handy during board bring-up to check that a register is writable and connected.
+    pub fn write_verify(&self, value: T) -> bool {
+        self.write(value);
+        self.read() == value
+    }
+
+    /// Like [`RW::write_verify`], but reports failed accesses and what was read back.
+    pub fn try_write_verify(&self, value: T) -> Result<(), WriteVerifyError<T>> {
+        self.try_write(value)?;
+        let read = self.try_read()?;
+        if read == value {
+            Ok(())
+        } else {
+            Err(WriteVerifyError::Mismatch {
+                address: self.0 as usize,
+                written: value,
+                read,
+            })
+        }
+    }
+}
+
+/// a MMIO register pointer that can be read, without assuming the register is aligned
+///
+/// Returned for `unchecked` registers. Unlike [`RO`] it only has an `unsafe` read, as not
+/// every target supports unaligned accesses to device memory.
+pub struct UnalignedRO<'a, T>(*const T, PhantomData<&'a T>);
+impl<T: MmioPrimitive> UnalignedRO<'_, T> {
+    /// # Safety
+    /// `address` must point to a valid MMIO register of type `T` for as long as the returned
+    /// value is used. It doesn't need to be aligned.
+    pub const unsafe fn new(address: usize) -> Self {
+        UnalignedRO(address as *const T, PhantomData)
+    }
+    /// Reads the register without assuming it is aligned.
+    ///
+    /// # Safety
+    /// The target must support an unaligned access to this register.
+    pub unsafe fn read_unchecked(&self) -> T {
+        read_volatile(self.0.cast::<Packed<T>>()).0
+    }
+}
+
+/// a MMIO register pointer that can be written to, without assuming the register is aligned
+///
+/// Returned for `unchecked` registers. Unlike [`WO`] it only has an `unsafe` write, as not
+/// every target supports unaligned accesses to device memory.
+pub struct UnalignedWO<'a, T>(*mut T, PhantomData<&'a mut T>);
+impl<T: MmioPrimitive> UnalignedWO<'_, T> {
+    /// # Safety
+    /// `address` must point to a valid MMIO register of type `T` for as long as the returned
+    /// value is used. It doesn't need to be aligned.
+    pub const unsafe fn new(address: usize) -> Self {
+        UnalignedWO(address as *mut T, PhantomData)
+    }
+    /// Writes the register without assuming it is aligned.
+    ///
+    /// # Safety
+    /// The target must support an unaligned access to this register.
+    pub unsafe fn write_unchecked(&self, value: T) {
+        write_volatile(self.0.cast::<Packed<T>>(), Packed(value))
+    }
+}
+
+/// a MMIO register pointer that can be read and written to, without assuming the register
+/// is aligned
+///
+/// Returned for `unchecked` registers. Unlike [`RW`] it only has `unsafe` accesses, as not
+/// every target supports unaligned accesses to device memory.
+pub struct UnalignedRW<'a, T>(*mut T, PhantomData<&'a mut T>);
+impl<T: MmioPrimitive> UnalignedRW<'_, T> {
+    /// # Safety
+    /// `address` must point to a valid MMIO register of type `T` for as long as the returned
+    /// value is used. It doesn't need to be aligned.
+    pub const unsafe fn new(address: usize) -> Self {
+        UnalignedRW(address as *mut T, PhantomData)
+    }
+    /// Reads the register without assuming it is aligned.
+    ///
+    /// # Safety
+    /// The target must support an unaligned access to this register.
+    pub unsafe fn read_unchecked(&self) -> T {
+        read_volatile(self.0.cast::<Packed<T>>()).0
+    }
+    /// Writes the register without assuming it is aligned.
+    ///
+    /// # Safety
+    /// The target must support an unaligned access to this register.
+    pub unsafe fn write_unchecked(&self, value: T) {
+        write_volatile(self.0.cast::<Packed<T>>(), Packed(value))
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+    pub trait Primitive {}
+}
+
+/// Types that can be read and written with a single volatile access.
+///
+/// This trait is sealed and implemented for the integer types (`u8`..`u128`, `usize`,
+/// `i8`..`i128`, `isize`): plain data that is valid for any bit pattern a register may hold.
+/// The register wrappers only work with these types, so e.g. `RW<String>` can't be read.
+/// `RO`, `WO` and `RW` of arrays of them, as declared with `allow_any_type`, access one
+/// element at a time instead.
+pub trait MmioPrimitive: sealed::Primitive + Copy {}
+
+macro_rules! impl_mmio_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Primitive for $ty {}
+            impl MmioPrimitive for $ty {}
+        )*
+    };
+}
+impl_mmio_primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Unsigned integer types that support the single-bit helpers on [`RW`].
+///
+/// This trait is sealed and implemented for `u8`, `u16`, `u32`, `u64`, `u128` and `usize`.
+pub trait Bits:
+    sealed::Sealed
+    + MmioPrimitive
+    + PartialEq
+    + core::ops::BitAnd<Output = Self>
+    + core::ops::BitOr<Output = Self>
+    + core::ops::BitXor<Output = Self>
+    + core::ops::Not<Output = Self>
+    + core::ops::Shl<u32, Output = Self>
+    + core::ops::Shr<u32, Output = Self>
+{
+    /// Width of the type in bits.
+    const BITS: u32;
+    const ZERO: Self;
+    /// A value with only bit `n` set.
+    fn bit(n: u32) -> Self;
+}
+
+macro_rules! impl_bits {
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl Bits for $ty {
+                const BITS: u32 = <$ty>::BITS;
+                const ZERO: Self = 0;
+                fn bit(n: u32) -> Self {
+                    1 << n
+                }
+            }
+        )*
+    };
+}
+impl_bits!(u8, u16, u32, u64, u128, usize);
+
+impl<T: Bits> RW<'_, T> {
+    /// Replaces the bits selected by `mask` with those of `value` in a single
+    /// read-modify-write, leaving all other bits alone.
+    pub fn write_masked(&self, value: T, mask: T) {
+        self.modify(|old| (old & !mask) | (value & mask));
+    }
+    /// Sets bit `n` with a read-modify-write.
+    pub fn set_bit(&self, n: u32) {
+        debug_assert!(
+            n < T::BITS,
+            "bit {} out of range for a {}-bit register",
+            n,
+            T::BITS
+        );
+        self.modify(|v| v | T::bit(n));
+    }
+    /// Clears bit `n` with a read-modify-write.
+    pub fn clear_bit(&self, n: u32) {
+        debug_assert!(
+            n < T::BITS,
+            "bit {} out of range for a {}-bit register",
+            n,
+            T::BITS
+        );
+        self.modify(|v| v & !T::bit(n));
+    }
+    /// Flips bit `n` with a read-modify-write.
+    pub fn toggle_bit(&self, n: u32) {
+        debug_assert!(
+            n < T::BITS,
+            "bit {} out of range for a {}-bit register",
+            n,
+            T::BITS
+        );
+        self.modify(|v| v ^ T::bit(n));
+    }
+    /// Reads the register and returns whether bit `n` is set.
+    pub fn is_bit_set(&self, n: u32) -> bool {
+        debug_assert!(
+            n < T::BITS,
+            "bit {} out of range for a {}-bit register",
+            n,
+            T::BITS
+        );
+        self.read() & T::bit(n) != T::ZERO
+    }
+    /// Reads the register and returns the bits in `range`, shifted down to bit 0.
+    pub fn read_bits(&self, range: core::ops::Range<u32>) -> T {
+        let mask = field_mask::<T>(&range);
+        if mask == T::ZERO {
+            return T::ZERO;
+        }
+        (self.read() >> range.start) & mask
+    }
+    /// Replaces the bits in `range` with the low bits of `value` with a read-modify-write.
+    /// Bits of `value` that don't fit in the range are ignored.
+    pub fn write_bits(&self, range: core::ops::Range<u32>, value: T) {
+        let mask = field_mask::<T>(&range);
+        if mask == T::ZERO {
+            return;
+        }
+        self.write_masked(value << range.start, mask << range.start);
+    }
+    /// Reads the register until all bits in `mask` are set.
+    pub fn poll_set(&self, mask: T) {
+        let _ = __private::poll(|| self.read(), mask, true, None);
+    }
+    /// Reads the register until all bits in `mask` are clear.
+    pub fn poll_clear(&self, mask: T) {
+        let _ = __private::poll(|| self.read(), mask, false, None);
+    }
+    /// Like [`Self::poll_set`], but gives up after reading the register `iterations` times.
+    pub fn poll_set_timeout(&self, mask: T, iterations: usize) -> Result<(), TimeoutError> {
+        __private::poll(|| self.read(), mask, true, Some(iterations))
+    }
+    /// Like [`Self::poll_clear`], but gives up after reading the register `iterations` times.
+    pub fn poll_clear_timeout(&self, mask: T, iterations: usize) -> Result<(), TimeoutError> {
+        __private::poll(|| self.read(), mask, false, Some(iterations))
+    }
+}
+
+impl<T: Bits> RO<'_, T> {
+    /// Reads the register until all bits in `mask` are set.
+    pub fn poll_set(&self, mask: T) {
+        let _ = __private::poll(|| self.read(), mask, true, None);
+    }
+    /// Reads the register until all bits in `mask` are clear.
+    pub fn poll_clear(&self, mask: T) {
+        let _ = __private::poll(|| self.read(), mask, false, None);
+    }
+    /// Like [`Self::poll_set`], but gives up after reading the register `iterations` times.
+    pub fn poll_set_timeout(&self, mask: T, iterations: usize) -> Result<(), TimeoutError> {
+        __private::poll(|| self.read(), mask, true, Some(iterations))
+    }
+    /// Like [`Self::poll_clear`], but gives up after reading the register `iterations` times.
+    pub fn poll_clear_timeout(&self, mask: T, iterations: usize) -> Result<(), TimeoutError> {
+        __private::poll(|| self.read(), mask, false, Some(iterations))
+    }
+}
+
+/// Ones in the low `range.len()` bits.
+fn field_mask<T: Bits>(range: &core::ops::Range<u32>) -> T {
+    debug_assert!(
+        range.start <= range.end && range.end <= T::BITS,
+        "bits {}..{} out of range for a {}-bit register",
+        range.start,
+        range.end,
+        T::BITS
+    );
+    let width = range.end.saturating_sub(range.start);
+    if width == 0 {
+        T::ZERO
+    } else {
+        !T::ZERO >> (T::BITS - width.min(T::BITS))
+    }
 }
 
 /// a MMIO register pointer that can be written to to clear the register
-pub struct WC<T>(*mut T);
-impl<T: Default> WC<T> {
-    pub unsafe fn new(address: usize) -> Self {
-        WC(address as *mut T)
+///
+/// [`WC::clear`] writes `T::default()` unless the pointer was created with
+/// [`WC::with_clear_value`], e.g. for registers that clear on writing all ones.
+pub struct WC<'a, T>(*mut T, Option<T>, PhantomData<&'a mut T>);
+impl<'a, T: MmioPrimitive> WC<'a, T> {
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// for as long as the returned value is used. Misalignment is caught by a debug
+    /// assertion.
+    pub const unsafe fn new(address: usize) -> Self {
+        debug_assert_aligned::<T>(address);
+        WC(address as *mut T, None, PhantomData)
     }
+    /// Like [`WC::new`], but [`WC::clear`] writes `clear_value` instead of `T::default()`.
+    ///
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// for as long as the returned value is used.
+    pub const unsafe fn with_clear_value(address: usize, clear_value: T) -> Self {
+        debug_assert_aligned::<T>(address);
+        WC(address as *mut T, Some(clear_value), PhantomData)
+    }
+    /// Like [`WC::new`], but borrows `region` so the pointer can't outlive the mapping.
+    ///
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// inside `region`.
+    pub const unsafe fn with_region<R: ?Sized>(address: usize, _region: &'a R) -> Self {
+        Self::new(address)
+    }
+    /// Clears the register by writing `value`.
+    pub fn clear_with(&self, value: T) {
+        unsafe { write_volatile(self.0, value) }
+    }
+}
+impl<T: MmioPrimitive + Default> WC<'_, T> {
     pub fn clear(&self) {
-        // todo: we don't really need to use Default here, but it's a good placeholder
-        unsafe { self.0.write_volatile(T::default()) }
+        self.clear_with(self.1.unwrap_or_default())
     }
 }
 
 /// a MMIO register pointer that can be read from and doing so will clear the register
-pub struct RC<T>(*mut T);
-impl<T> RC<T> {
-    pub unsafe fn new(address: usize) -> Self {
-        RC(address as *mut T)
+pub struct RC<'a, T>(*mut T, PhantomData<&'a mut T>);
+impl<'a, T: MmioPrimitive> RC<'a, T> {
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// for as long as the returned value is used. Misalignment is caught by a debug
+    /// assertion.
+    pub const unsafe fn new(address: usize) -> Self {
+        debug_assert_aligned::<T>(address);
+        RC(address as *mut T, PhantomData)
+    }
+    /// Like [`RC::new`], but borrows `region` so the pointer can't outlive the mapping.
+    ///
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// inside `region`.
+    pub const unsafe fn with_region<R: ?Sized>(address: usize, _region: &'a R) -> Self {
+        Self::new(address)
+    }
+    #[must_use = "reading clears the register, so the value is lost if it's not used"]
+    pub fn read(&self) -> T {
+        unsafe { read_volatile(self.0) }
+    }
+    /// Reads the register, reporting a failed access instead of assuming it succeeds.
+    #[must_use = "reading clears the register, so the value is lost if it's not used"]
+    pub fn try_read(&self) -> Result<T, AccessError> {
+        unsafe { try_read_volatile(self.0) }
+    }
+}
+
+/// a MMIO register pointer to a write-one-to-clear register
+///
+/// Reading returns the current flags, writing a mask acknowledges (clears) the flags whose
+/// bits are set in it and leaves the others alone.
+pub struct W1C<'a, T>(*mut T, PhantomData<&'a mut T>);
+impl<'a, T: MmioPrimitive> W1C<'a, T> {
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// for as long as the returned value is used. Misalignment is caught by a debug
+    /// assertion.
+    pub const unsafe fn new(address: usize) -> Self {
+        debug_assert_aligned::<T>(address);
+        W1C(address as *mut T, PhantomData)
+    }
+    /// Like [`W1C::new`], but borrows `region` so the pointer can't outlive the mapping.
+    ///
+    /// # Safety
+    /// `address` must point to a valid, suitably aligned MMIO register of type `T`
+    /// inside `region`.
+    pub const unsafe fn with_region<R: ?Sized>(address: usize, _region: &'a R) -> Self {
+        Self::new(address)
     }
     pub fn read(&self) -> T {
-        unsafe { self.0.read_volatile() }
+        unsafe { read_volatile(self.0) }
+    }
+    /// Reads the register, reporting a failed access instead of assuming it succeeds.
+    pub fn try_read(&self) -> Result<T, AccessError> {
+        unsafe { try_read_volatile(self.0) }
+    }
+    /// Clears the flags set in `mask` by writing it to the register.
+    pub fn ack(&self, mask: T) {
+        unsafe { write_volatile(self.0, mask) }
     }
 }
@@ -0,0 +1,66 @@
+//! Locks owned by blocks declared with `#[register_block(lock)]`, so that multi-register
+//! sequences from different contexts don't interleave.
+#[cfg(target_has_atomic = "8")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A lock held by a block for the duration of `locked(|block| ...)`.
+///
+/// Every block value has its own lock, so only contexts sharing the same block are kept apart.
+///
+/// # Safety
+/// `lock` must not return while another context holds the lock, and acquiring it must
+/// synchronise with the `unlock` that released it, as with `Acquire` and `Release`.
+pub unsafe trait RawLock {
+    /// The lock in its unlocked state, for the `const fn new` of the block.
+    const INIT: Self;
+    /// Waits until the lock is free and takes it.
+    fn lock(&self);
+    /// Releases the lock.
+    ///
+    /// # Safety
+    /// Only the context holding the lock may release it.
+    unsafe fn unlock(&self);
+}
+
+/// A spinlock, the lock of blocks with a plain `lock` option.
+///
+/// Taking it spins until it is free, so it must not be taken by an interrupt handler that can
+/// preempt a holder on the same core: that deadlocks. Use a lock that masks interrupts there.
+#[cfg(target_has_atomic = "8")]
+#[derive(Debug)]
+pub struct SpinLock(AtomicBool);
+
+#[cfg(target_has_atomic = "8")]
+unsafe impl RawLock for SpinLock {
+    const INIT: Self = SpinLock(AtomicBool::new(false));
+
+    fn lock(&self) {
+        while self
+            .0
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+    }
+
+    unsafe fn unlock(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// Releases the lock when dropped, so a panicking closure doesn't leave it taken.
+struct Guard<'a, L: RawLock>(&'a L);
+
+impl<L: RawLock> Drop for Guard<'_, L> {
+    fn drop(&mut self) {
+        unsafe { self.0.unlock() }
+    }
+}
+
+/// Runs `f` with `lock` held, for the generated `locked`.
+pub fn with_lock<L: RawLock, R>(lock: &L, f: impl FnOnce() -> R) -> R {
+    lock.lock();
+    let _guard = Guard(lock);
+    f()
+}
@@ -0,0 +1,119 @@
+//! Register blocks backed by an `mmap`ed range, for drivers running in Linux user space.
+use core::ffi::{c_int, c_long, c_void};
+use core::ops::Deref;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+
+use crate::RegisterBlockView;
+
+const PROT_READ: c_int = 0x1;
+const PROT_WRITE: c_int = 0x2;
+const MAP_SHARED: c_int = 0x01;
+const O_SYNC: c_int = 0o4010000;
+const SC_PAGESIZE: c_int = 30;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: c_long,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    fn sysconf(name: c_int) -> c_long;
+}
+
+/// A register block living in a mapping that is unmapped again when the guard is dropped.
+///
+/// Derefs to the block, so register wrappers borrowed from it can't outlive the mapping.
+pub struct MappedBlock<B> {
+    /// start of the mapping, which starts at the page containing the block
+    mapping: *mut c_void,
+    mapping_len: usize,
+    block: B,
+}
+
+impl<B: RegisterBlockView> MappedBlock<B> {
+    /// Maps `len` bytes of physical memory at `address` through `/dev/mem` and builds the
+    /// block on top of it with `make`, e.g. `MappedBlock::map_physical(0x3F20_1000, 0x90, Uart::new)`.
+    ///
+    /// # Safety
+    /// The range must hold the registers of the block, and no other code may access them in
+    /// ways that conflict with the driver.
+    pub unsafe fn map_physical(
+        address: usize,
+        len: usize,
+        make: impl FnOnce(usize) -> B,
+    ) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(O_SYNC)
+            .open("/dev/mem")?;
+        Self::map(&file, address, len, make)
+    }
+
+    /// Maps `len` bytes of `file` at `offset` and builds the block on top of it with `make`.
+    ///
+    /// `offset` doesn't have to be page aligned. Fails if the block doesn't fit in `len`
+    /// bytes.
+    ///
+    /// # Safety
+    /// The mapped range must hold the registers of the block, and no other code may access
+    /// them in ways that conflict with the driver.
+    pub unsafe fn map(
+        file: &File,
+        offset: usize,
+        len: usize,
+        make: impl FnOnce(usize) -> B,
+    ) -> io::Result<Self> {
+        let page = sysconf(SC_PAGESIZE) as usize;
+        let start = offset - offset % page;
+        let mapping_len = len + (offset - start);
+        let file_offset = c_long::try_from(start)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "offset out of range"))?;
+        let mapping = mmap(
+            core::ptr::null_mut(),
+            mapping_len,
+            PROT_READ | PROT_WRITE,
+            MAP_SHARED,
+            file.as_raw_fd(),
+            file_offset,
+        );
+        // MAP_FAILED
+        if mapping as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let guard = MappedBlock {
+            mapping,
+            mapping_len,
+            block: make(mapping as usize + (offset - start)),
+        };
+        if guard.block.block_size() > len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the register block doesn't fit in the mapping",
+            ));
+        }
+        Ok(guard)
+    }
+}
+
+impl<B> Deref for MappedBlock<B> {
+    type Target = B;
+    fn deref(&self) -> &B {
+        &self.block
+    }
+}
+
+impl<B> Drop for MappedBlock<B> {
+    fn drop(&mut self) {
+        unsafe {
+            munmap(self.mapping, self.mapping_len);
+        }
+    }
+}
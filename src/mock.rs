@@ -0,0 +1,270 @@
+//! An in-memory stand-in for MMIO, for testing drivers without hardware.
+//!
+//! With the `mock` feature, every access through the register wrappers first checks whether
+//! a backend has been [`install`]ed on the current thread and, if so, goes to the backend
+//! instead of memory. Without a backend the wrappers fall back to plain volatile accesses.
+//! Atomic accesses through [`AtomicRW`](crate::AtomicRW) are never mocked.
+use std::cell::RefCell;
+use std::mem::{size_of, MaybeUninit};
+use std::rc::Rc;
+
+use crate::{AccessError, RegisterInfo};
+
+/// Handles the register accesses of the current thread while installed.
+pub trait MmioBackend {
+    /// Reads `bytes.len()` bytes of the register at `address` into `bytes`, in native order.
+    fn read(&mut self, address: usize, bytes: &mut [u8]);
+    /// Writes `bytes` to the register at `address`, in native order.
+    fn write(&mut self, address: usize, bytes: &[u8]);
+    /// Like [`MmioBackend::read`], but may fail, e.g. to inject faults. Backs the `try_read`
+    /// methods of the wrappers.
+    fn try_read(&mut self, address: usize, bytes: &mut [u8]) -> Result<(), AccessError> {
+        self.read(address, bytes);
+        Ok(())
+    }
+    /// Like [`MmioBackend::write`], but may fail, e.g. to inject faults. Backs the
+    /// `try_write` methods of the wrappers.
+    fn try_write(&mut self, address: usize, bytes: &[u8]) -> Result<(), AccessError> {
+        self.write(address, bytes);
+        Ok(())
+    }
+}
+
+thread_local! {
+    static BACKEND: RefCell<Option<Box<dyn MmioBackend>>> = const { RefCell::new(None) };
+}
+
+/// Routes all register accesses of the current thread to `backend`, replacing any backend
+/// installed before.
+pub fn install(backend: impl MmioBackend + 'static) {
+    BACKEND.with(|slot| *slot.borrow_mut() = Some(Box::new(backend)));
+}
+
+/// Removes the backend of the current thread, so accesses go to memory again.
+pub fn uninstall() {
+    BACKEND.with(|slot| *slot.borrow_mut() = None);
+}
+
+/// Reads a `T` from the installed backend, if there is one.
+///
+/// # Safety
+/// The backend must produce a valid bit pattern for `T`.
+pub(crate) unsafe fn read<T>(ptr: *const T) -> Option<T> {
+    BACKEND.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let backend = slot.as_mut()?;
+        let mut value = MaybeUninit::<T>::zeroed();
+        let bytes = std::slice::from_raw_parts_mut(value.as_mut_ptr().cast::<u8>(), size_of::<T>());
+        backend.read(ptr as usize, bytes);
+        Some(value.assume_init())
+    })
+}
+
+/// Writes `value` to the installed backend, handing it back if there is none.
+pub(crate) fn write<T>(ptr: *mut T, value: T) -> Option<T> {
+    BACKEND.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let Some(backend) = slot.as_mut() else {
+            return Some(value);
+        };
+        let bytes = unsafe {
+            std::slice::from_raw_parts((&value as *const T).cast::<u8>(), size_of::<T>())
+        };
+        backend.write(ptr as usize, bytes);
+        None
+    })
+}
+
+/// Fallible version of [`read`].
+///
+/// # Safety
+/// The backend must produce a valid bit pattern for `T`.
+pub(crate) unsafe fn try_read<T>(ptr: *const T) -> Option<Result<T, AccessError>> {
+    BACKEND.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let backend = slot.as_mut()?;
+        let mut value = MaybeUninit::<T>::zeroed();
+        let bytes = std::slice::from_raw_parts_mut(value.as_mut_ptr().cast::<u8>(), size_of::<T>());
+        Some(
+            backend
+                .try_read(ptr as usize, bytes)
+                .map(|()| value.assume_init()),
+        )
+    })
+}
+
+/// Fallible version of [`write`], handing `value` back if there is no backend.
+pub(crate) fn try_write<T>(ptr: *mut T, value: T) -> Result<Result<(), AccessError>, T> {
+    BACKEND.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let Some(backend) = slot.as_mut() else {
+            return Err(value);
+        };
+        let bytes = unsafe {
+            std::slice::from_raw_parts((&value as *const T).cast::<u8>(), size_of::<T>())
+        };
+        Ok(backend.try_write(ptr as usize, bytes))
+    })
+}
+
+/// A register access recorded by [`VecBackend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockAccess {
+    /// `value` was read from `address`.
+    Read { address: usize, value: u128 },
+    /// `value` was written to `address`.
+    Write { address: usize, value: u128 },
+}
+
+impl MockAccess {
+    /// Address of the register that was accessed.
+    pub fn address(&self) -> usize {
+        match *self {
+            MockAccess::Read { address, .. } | MockAccess::Write { address, .. } => address,
+        }
+    }
+}
+
+struct VecBackendInner {
+    base: usize,
+    memory: Vec<u8>,
+    log: Vec<MockAccess>,
+}
+
+/// A backend holding `len` bytes of register memory starting at `base`, which records every
+/// access.
+///
+/// Clones share the same memory and log, so keep one around to inspect after
+/// [`install`]ing another. Accesses outside of `base..base + len` panic.
+#[derive(Clone)]
+pub struct VecBackend(Rc<RefCell<VecBackendInner>>);
+
+impl VecBackend {
+    /// Zero-initialised register memory for the `len` bytes from `base`.
+    pub fn new(base: usize, len: usize) -> Self {
+        VecBackend(Rc::new(RefCell::new(VecBackendInner {
+            base,
+            memory: vec![0; len],
+            log: Vec::new(),
+        })))
+    }
+
+    /// Register memory for the `len` bytes from `base` in its power-on state: every register
+    /// in `registers` that declares a `reset` value holds it, all other bytes are zero.
+    ///
+    /// `registers` is the `REGISTERS` table of the block under test, e.g.
+    /// `VecBackend::with_resets(base, Uart::<usize>::BLOCK_SIZE, Uart::<usize>::REGISTERS)`.
+    pub fn with_resets(base: usize, len: usize, registers: &[RegisterInfo]) -> Self {
+        let backend = Self::new(base, len);
+        for info in registers {
+            let Some(reset) = info.reset else {
+                continue;
+            };
+            let bytes = from_u128(reset, info.width as usize / 8);
+            for index in 0..info.count {
+                backend.set(base + info.offset + index * info.stride, &bytes);
+            }
+        }
+        backend
+    }
+
+    /// The accesses made so far, oldest first.
+    pub fn log(&self) -> Vec<MockAccess> {
+        self.0.borrow().log.clone()
+    }
+
+    /// How many times the register `offset` bytes from the backend's base has been read
+    /// since the log was last cleared.
+    pub fn read_count(&self, offset: usize) -> usize {
+        let inner = self.0.borrow();
+        let address = inner.base + offset;
+        inner
+            .log
+            .iter()
+            .filter(|access| {
+                matches!(access, MockAccess::Read { .. }) && access.address() == address
+            })
+            .count()
+    }
+
+    /// How many times the register `offset` bytes from the backend's base has been written
+    /// since the log was last cleared.
+    pub fn write_count(&self, offset: usize) -> usize {
+        let inner = self.0.borrow();
+        let address = inner.base + offset;
+        inner
+            .log
+            .iter()
+            .filter(|access| {
+                matches!(access, MockAccess::Write { .. }) && access.address() == address
+            })
+            .count()
+    }
+
+    /// Forgets the accesses made so far.
+    pub fn clear_log(&self) {
+        self.0.borrow_mut().log.clear()
+    }
+
+    /// The current contents of the register memory.
+    pub fn memory(&self) -> Vec<u8> {
+        self.0.borrow().memory.clone()
+    }
+
+    /// Overwrites register memory at `address` without recording an access, e.g. to
+    /// simulate the hardware setting a status flag.
+    pub fn set(&self, address: usize, bytes: &[u8]) {
+        let mut inner = self.0.borrow_mut();
+        let range = inner.range(address, bytes.len());
+        inner.memory[range].copy_from_slice(bytes);
+    }
+}
+
+impl VecBackendInner {
+    fn range(&self, address: usize, len: usize) -> std::ops::Range<usize> {
+        let start = address
+            .checked_sub(self.base)
+            .filter(|start| start + len <= self.memory.len())
+            .unwrap_or_else(|| panic!("mock access at {:#x} is outside of the backend", address));
+        start..start + len
+    }
+}
+
+/// Packs up to 16 native-order bytes into a `u128` for the log.
+fn to_u128(bytes: &[u8]) -> u128 {
+    let mut value = [0u8; 16];
+    if cfg!(target_endian = "little") {
+        value[..bytes.len()].copy_from_slice(bytes);
+        u128::from_le_bytes(value)
+    } else {
+        value[16 - bytes.len()..].copy_from_slice(bytes);
+        u128::from_be_bytes(value)
+    }
+}
+
+/// The `len` native-order bytes of `value`, the inverse of [`to_u128`].
+fn from_u128(value: u128, len: usize) -> Vec<u8> {
+    if cfg!(target_endian = "little") {
+        value.to_le_bytes()[..len].to_vec()
+    } else {
+        value.to_be_bytes()[16 - len..].to_vec()
+    }
+}
+
+impl MmioBackend for VecBackend {
+    fn read(&mut self, address: usize, bytes: &mut [u8]) {
+        let mut inner = self.0.borrow_mut();
+        let range = inner.range(address, bytes.len());
+        bytes.copy_from_slice(&inner.memory[range]);
+        let value = to_u128(bytes);
+        inner.log.push(MockAccess::Read { address, value });
+    }
+
+    fn write(&mut self, address: usize, bytes: &[u8]) {
+        let mut inner = self.0.borrow_mut();
+        let range = inner.range(address, bytes.len());
+        inner.memory[range].copy_from_slice(bytes);
+        let value = to_u128(bytes);
+        inner.log.push(MockAccess::Write { address, value });
+    }
+}
@@ -0,0 +1,161 @@
+//! 128-bit registers accessed as four 32-bit words, for buses without 128-bit transactions.
+use core::marker::PhantomData;
+
+use crate::{
+    debug_assert_aligned, read_volatile, try_read_volatile, try_write_volatile, write_volatile,
+    WriteVerifyError, RO, RW, WO,
+};
+
+/// Which 32-bit word of a split register is accessed first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordOrder {
+    /// Bits 0..32 first, bits 96..128 last.
+    LowFirst,
+    /// Bits 96..128 first, bits 0..32 last.
+    HighFirst,
+}
+
+impl WordOrder {
+    /// Indices of the words in access order, word `k` holding bits `32 * k..32 * (k + 1)`.
+    fn words(self) -> [usize; 4] {
+        match self {
+            WordOrder::LowFirst => [0, 1, 2, 3],
+            WordOrder::HighFirst => [3, 2, 1, 0],
+        }
+    }
+}
+
+/// Address of word `k` of the `u128` at `ptr`, laid out like a native `u128`.
+fn word(ptr: *mut u128, k: usize) -> *mut u32 {
+    let index = if cfg!(target_endian = "little") {
+        k
+    } else {
+        3 - k
+    };
+    ptr.cast::<u32>().wrapping_add(index)
+}
+
+unsafe fn read_words(ptr: *mut u128, order: WordOrder) -> u128 {
+    let mut value = 0;
+    for k in order.words() {
+        value |= (read_volatile(word(ptr, k)) as u128) << (32 * k);
+    }
+    value
+}
+
+unsafe fn write_words(ptr: *mut u128, order: WordOrder, value: u128) {
+    for k in order.words() {
+        write_volatile(word(ptr, k), (value >> (32 * k)) as u32);
+    }
+}
+
+/// A view of a 128-bit register pointer such as [`RW`] that splits every access into four
+/// 32-bit accesses, in the given [`WordOrder`].
+///
+/// The words are laid out like a native `u128`, so the value read is the same as a single
+/// 128-bit access would give; only the bus transactions differ. As every access is 32 bits
+/// wide, the register only needs to be 4-byte aligned: create it with `from_address` rather
+/// than wrapping a pointer that expects the alignment of a `u128`.
+pub struct Split<W>(W, WordOrder);
+impl<W> Split<W> {
+    pub const fn new(inner: W, order: WordOrder) -> Self {
+        Split(inner, order)
+    }
+}
+
+impl Split<RO<'_, u128>> {
+    /// The split register at `address`, which only needs the 4-byte alignment of its words
+    /// rather than that of a `u128`, as [`RO::new`] would.
+    ///
+    /// # Safety
+    /// `address` must point to a valid 128-bit MMIO register, aligned to 4 bytes, for as long
+    /// as the returned value is used. Misalignment is caught by a debug assertion.
+    pub const unsafe fn from_address(address: usize, order: WordOrder) -> Self {
+        debug_assert_aligned::<u32>(address);
+        Split(RO(address as *const u128, PhantomData), order)
+    }
+
+    pub fn read(&self) -> u128 {
+        unsafe { read_words(self.0 .0 as *mut u128, self.1) }
+    }
+}
+
+impl Split<WO<'_, u128>> {
+    /// The split register at `address`, which only needs the 4-byte alignment of its words
+    /// rather than that of a `u128`, as [`WO::new`] would.
+    ///
+    /// # Safety
+    /// `address` must point to a valid 128-bit MMIO register, aligned to 4 bytes, for as long
+    /// as the returned value is used. Misalignment is caught by a debug assertion.
+    pub const unsafe fn from_address(address: usize, order: WordOrder) -> Self {
+        debug_assert_aligned::<u32>(address);
+        Split(WO(address as *mut u128, PhantomData), order)
+    }
+
+    pub fn write(&self, value: u128) {
+        unsafe { write_words(self.0 .0, self.1, value) }
+    }
+}
+
+impl Split<RW<'_, u128>> {
+    /// The split register at `address`, which only needs the 4-byte alignment of its words
+    /// rather than that of a `u128`, as [`RW::new`] would.
+    ///
+    /// # Safety
+    /// `address` must point to a valid 128-bit MMIO register, aligned to 4 bytes, for as long
+    /// as the returned value is used. Misalignment is caught by a debug assertion.
+    pub const unsafe fn from_address(address: usize, order: WordOrder) -> Self {
+        debug_assert_aligned::<u32>(address);
+        Split(RW(address as *mut u128, PhantomData), order)
+    }
+
+    pub fn read(&self) -> u128 {
+        unsafe { read_words(self.0 .0, self.1) }
+    }
+
+    pub fn write(&self, value: u128) {
+        unsafe { write_words(self.0 .0, self.1, value) }
+    }
+
+    pub fn modify<F>(&self, f: F)
+    where
+        F: FnOnce(u128) -> u128,
+    {
+        self.write(f(self.read()));
+    }
+
+    /// Replaces the bits selected by `mask` with those of `value` in a single
+    /// read-modify-write, leaving all other bits alone.
+    pub fn write_masked(&self, value: u128, mask: u128) {
+        self.modify(|old| (old & !mask) | (value & mask));
+    }
+
+    /// Writes `value`, then reads the register back and returns whether it holds `value`.
+    pub fn write_verify(&self, value: u128) -> bool {
+        self.write(value);
+        self.read() == value
+    }
+
+    /// Like [`Split::write_verify`], but reports failed accesses and what was read back.
+    pub fn try_write_verify(&self, value: u128) -> Result<(), WriteVerifyError<u128>> {
+        let ptr = self.0 .0;
+        let mut read = 0;
+        unsafe {
+            for k in self.1.words() {
+                try_write_volatile(word(ptr, k), (value >> (32 * k)) as u32)?;
+            }
+            for k in self.1.words() {
+                read |= (try_read_volatile(word(ptr, k))? as u128) << (32 * k);
+            }
+        }
+        if read == value {
+            Ok(())
+        } else {
+            Err(WriteVerifyError::Mismatch {
+                address: ptr as usize,
+                written: value,
+                read,
+            })
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Tokens handed out at most once per base address, so that two drivers can't end up on the
+//! same registers by accident.
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of distinct base addresses that [`BaseToken::take`] can hand out tokens for.
+const CAPACITY: usize = 64;
+
+/// Marks a slot of `TAKEN` no base has been recorded in yet.
+const FREE: usize = usize::MAX;
+
+/// The bases tokens have been taken for, filled front to back and never cleared.
+static TAKEN: [AtomicUsize; CAPACITY] = [const { AtomicUsize::new(FREE) }; CAPACITY];
+
+/// Proof of exclusive ownership of the registers at `ADDR`: [`BaseToken::take`] returns it
+/// only once per address for the whole program.
+///
+/// Zero-sized and neither `Copy` nor `Clone`. A block with the `exclusive` option is built
+/// from one with `from_token`, which consumes it; dropping a token doesn't give the base back.
+///
+/// Tokens can be taken for at most 64 distinct base addresses, as the taken ones are recorded
+/// in a fixed table. `ADDR` can't be `usize::MAX`.
+#[derive(Debug)]
+pub struct BaseToken<const ADDR: usize> {
+    _private: (),
+}
+
+impl<const ADDR: usize> BaseToken<ADDR> {
+    /// The base address the token is for.
+    pub const ADDR: usize = ADDR;
+
+    /// The token for `ADDR`, or `None` if it has been taken before.
+    ///
+    /// Also `None` once tokens have been taken for 64 other base addresses. Lock-free, so it
+    /// can be called from interrupt handlers too.
+    pub fn take() -> Option<Self> {
+        const { assert!(ADDR != FREE, "`usize::MAX` can't be the base of a token") };
+        for slot in &TAKEN {
+            // a slot is claimed once and never changes again, so the first free one ends the
+            // search: `ADDR` isn't in the table before it
+            match slot.compare_exchange(FREE, ADDR, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return Some(BaseToken { _private: () }),
+                Err(taken) if taken == ADDR => return None,
+                Err(_) => {}
+            }
+        }
+        None
+    }
+}
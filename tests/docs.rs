@@ -0,0 +1,22 @@
+//! The rendered docs of a generated block keep the docs of the input struct.
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn struct_docs_survive() {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("docs");
+    let status = Command::new(env!("CARGO"))
+        .args(["doc", "--quiet", "--no-deps", "--example", "registers"])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .expect("failed to run cargo doc");
+    assert!(status.success());
+    let html = std::fs::read_to_string(target_dir.join("doc/registers/struct.Adc.html"))
+        .expect("no docs rendered for `Adc`");
+    assert!(html.contains("The analog-to-digital converter, with four channels."));
+    // followed by the memory map
+    assert!(html.contains("<td><code>ctrl</code></td>"));
+    assert!(html.contains("<td>Control register.</td>"));
+}
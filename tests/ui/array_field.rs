@@ -0,0 +1,9 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Regs {
+    #[register(offset = 0x00, access = "RW")]
+    triple: [u8; 3],
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Regs {
+    #[register(offset = 0xFFFF_FFF0, access = "RW", count = 0x1000_0000, stride = 0x10)]
+    data: u32,
+}
+
+fn main() {}
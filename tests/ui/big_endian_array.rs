@@ -0,0 +1,9 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Regs {
+    #[register(offset = 0x00, access = "RO", endian = "big", allow_any_type)]
+    id: [u8; 4],
+}
+
+fn main() {}
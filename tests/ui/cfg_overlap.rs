@@ -0,0 +1,13 @@
+use register_block::register_block;
+
+// a conditional register still may not overlap one that always exists
+#[register_block]
+pub struct Timer {
+    #[cfg(not(target_pointer_width = "16"))]
+    #[register(offset = 0x00, access = "RW")]
+    wide_ctrl: u32,
+    #[register(offset = 0x02, access = "RW")]
+    status: u16,
+}
+
+fn main() {}
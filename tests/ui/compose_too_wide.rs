@@ -0,0 +1,12 @@
+use register_block::register_block;
+
+#[register_block]
+#[compose(name = "count", parts = [count_lo, count_hi], ty = u32)]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RO")]
+    count_lo: u32,
+    #[register(offset = 0x04, access = "RO")]
+    count_hi: u32,
+}
+
+fn main() {}
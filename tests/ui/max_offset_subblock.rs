@@ -0,0 +1,20 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Channel {
+    #[register(offset = 0x00, access = "RW")]
+    src: u32,
+    #[register(offset = 0x04, access = "RW")]
+    len: u32,
+}
+
+// the sub-block starts below max_offset, but its registers reach past it
+#[register_block(max_offset = 0x1F)]
+pub struct Dma {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[subblock(offset = 0x1C)]
+    chan: Channel,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Regs {
+    #[register(offset = 0x00, acess = "RW")]
+    ctrl: u32,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#![deny(unused_must_use)]
+
+use register_block::register_block;
+
+#[register_block(flat, must_use)]
+pub struct Regs {
+    #[register(offset = 0x00, access = "RO")]
+    status: u32,
+    #[register(offset = 0x04, access = "RC")]
+    events: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    let regs = Regs::new(buffer.as_mut_ptr() as usize);
+    regs.read_status();
+    regs.read_events();
+}
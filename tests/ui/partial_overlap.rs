@@ -0,0 +1,11 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Regs {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x02, access = "RW")]
+    flags: u8,
+}
+
+fn main() {}
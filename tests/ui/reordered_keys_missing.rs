@@ -0,0 +1,12 @@
+use register_block::register_block;
+
+// keys may come in any order, but a missing one is still reported
+#[register_block]
+pub struct Regs {
+    #[register(access = "RW", offset = 0x00)]
+    ctrl: u32,
+    #[register(count = 2, offset = 0x04, strid = 4)]
+    status: u32,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[reserved(offset = 0x40, len = 0xC0)]
+    _gap: (),
+    #[register(offset = 0x80, access = "RW")]
+    late: u32,
+}
+
+fn main() {}
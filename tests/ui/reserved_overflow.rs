@@ -0,0 +1,10 @@
+use register_block::register_block;
+
+#[register_block]
+#[reserved(offset = 0xFFFF_FFF0, len = 0x20)]
+pub struct Regs {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use register_block::RW;
+
+fn main() {
+    let mut buffer = [0u64; 4];
+    let reg = unsafe { RW::<String>::new(buffer.as_mut_ptr() as usize) };
+    let _ = reg.read();
+}
@@ -0,0 +1,14 @@
+use register_block::register_block;
+
+pub struct Config {
+    pub mode: u16,
+    pub level: u16,
+}
+
+#[register_block]
+pub struct Regs {
+    #[register(offset = 0x00, access = "RW")]
+    config: Config,
+}
+
+fn main() {}
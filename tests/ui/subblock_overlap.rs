@@ -0,0 +1,20 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Channel {
+    #[register(offset = 0x00, access = "RW")]
+    src: u32,
+    #[register(offset = 0x04, access = "RW")]
+    len: u32,
+}
+
+// `ctrl` sits on top of the sub-block's `len`
+#[register_block]
+pub struct Dma {
+    #[register(offset = 0x04, access = "RW")]
+    ctrl: u32,
+    #[subblock(offset = 0x00)]
+    chan: Channel,
+}
+
+fn main() {}
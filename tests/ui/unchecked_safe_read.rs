@@ -0,0 +1,16 @@
+use register_block::register_block;
+
+#[register_block]
+pub struct Packed {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u8,
+    #[register(offset = 0x01, access = "RW", unchecked)]
+    data: u32,
+}
+
+fn main() {
+    let mut buffer = [0u32; 2];
+    let regs = Packed::new(buffer.as_mut_ptr() as usize);
+    // a misaligned read is only available through the `unsafe` `read_unchecked`
+    regs.data().read();
+}
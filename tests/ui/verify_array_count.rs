@@ -0,0 +1,12 @@
+use register_block::register_block;
+
+// the table predates the fourth channel
+#[register_block(verify = [(0x00, 4), (0x04, 4, count = 3)])]
+pub struct Adc {
+    #[register(offset = 0x00, access = "RW")]
+    ctrl: u32,
+    #[register(offset = 0x04, access = "RO", count = 4)]
+    data: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use register_block::register_block;
+
+// the row leaves out the stride, so it is taken to be the width
+#[register_block(verify = [(0x00, 4, count = 2)])]
+pub struct Timer {
+    #[register(offset = 0x00, access = "RW", count = 2, stride = 8)]
+    compare: u32,
+}
+
+fn main() {}